@@ -1,4 +1,18 @@
-use crate::{Color, Pen};
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+    ops::Range,
+    path::Path,
+};
+
+use crate::{
+    color::PixelFormat,
+    encoder::ImageEncoder,
+    font::{self, Font},
+    geometry::{Coord, Point},
+    Color, OwnedCanvas, Pen,
+};
 
 pub struct Canvas<'a> {
     buffer: &'a mut [u32],
@@ -6,25 +20,48 @@ pub struct Canvas<'a> {
     height: usize,
     clamped_width: i32,
     clamped_height: i32,
+    raster_op: RasterOp,
+    blend_mode: BlendMode,
 }
 
 impl<'a> Canvas<'a> {
     /// Creates a new [`Canvas`] with giver width and height.
     /// # Panics
-    /// This function panics if the supplied width and height does not match the buffer size.
+    /// This function panics if the supplied width and height does not match the buffer size
+    /// (`width * height` overflowing included).
     #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
     #[must_use]
     pub fn new(buffer: &'a mut [u32], width: usize, height: usize) -> Self {
-        assert!(buffer.len() == width * height);
+        // as width * height fits in a usize, no index of a pixel within the canvas can overflow
+        assert!(
+            width.checked_mul(height) == Some(buffer.len()),
+            "width * height does not match the buffer size"
+        );
         Self {
             buffer,
             width,
             height,
             clamped_width: width.min(i32::MAX as usize) as i32,
             clamped_height: height.min(i32::MAX as usize) as i32,
+            raster_op: RasterOp::Copy,
+            blend_mode: BlendMode::Replace,
         }
     }
 
+    /// Creates an [`OwnedCanvas`] of given width and height, for when bringing your own buffer is not needed.
+    /// Its pixels start out as 0 (transparent black); draw on it through [`OwnedCanvas::canvas`].
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut image = Canvas::with_size(16, 16);
+    /// image.canvas().fill_rect(0, 0, 4, 4, Color::RED);
+    ///
+    /// assert_eq!(image.buffer()[3 * 16 + 3], Color::RED.0);
+    /// ```
+    #[must_use]
+    pub fn with_size(width: usize, height: usize) -> OwnedCanvas {
+        OwnedCanvas::new(width, height)
+    }
+
     /// Returns the width of this [`Canvas`].
     #[must_use]
     pub fn width(&self) -> usize {
@@ -49,6 +86,56 @@ impl<'a> Canvas<'a> {
         self.buffer
     }
 
+    /// Returns the [`RasterOp`] shapes and text are currently drawn with.
+    #[must_use]
+    pub fn raster_op(&self) -> RasterOp {
+        self.raster_op
+    }
+
+    /// Sets how shapes and text drawn from now on are combined with the pixels already in this [`Canvas`].
+    /// Only drawing is affected: clearing, flood fills, filters and blits always write their colors as they are.
+    /// ```rust
+    /// use vason::{canvas::RasterOp, Canvas, Color};
+    /// let mut buffer = [Color::BLUE.0; 64];
+    /// let mut canvas = Canvas::new(&mut buffer, 8, 8);
+    /// canvas.set_raster_op(RasterOp::Xor);
+    ///
+    /// // a rubber band selection: drawing it again erases it
+    /// canvas.outline_rect(1, 1, 5, 4, Color::WHITE);
+    /// assert_eq!(canvas.buffer()[10], Color::BLUE.0 ^ Color::WHITE.0);
+    /// canvas.outline_rect(1, 1, 5, 4, Color::WHITE);
+    ///
+    /// assert_eq!(buffer, [Color::BLUE.0; 64]);
+    /// ```
+    pub fn set_raster_op(&mut self, raster_op: RasterOp) {
+        self.raster_op = raster_op;
+    }
+
+    /// Returns the [`BlendMode`] shapes and text are currently drawn with.
+    #[must_use]
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    /// Sets whether the alpha of the colors shapes and text are drawn with from now on is taken into account.
+    /// Like the [`RasterOp`], only drawing is affected, and with [`RasterOp::Xor`] the blend mode has no effect.
+    /// ```rust
+    /// use vason::{canvas::BlendMode, Canvas, Color};
+    /// let mut buffer = [0u32; 16];
+    /// let mut canvas = Canvas::new(&mut buffer, 4, 4);
+    /// canvas.clear(Color::BLUE);
+    /// canvas.set_blend_mode(BlendMode::Alpha);
+    ///
+    /// // half transparent red over blue
+    /// canvas.fill_rect(0, 0, 2, 2, Color::rgba(255, 0, 0, 128));
+    ///
+    /// assert_eq!(canvas.get_pixel(1, 1), Some(Color::rgb(128, 0, 127)));
+    /// assert_eq!(canvas.get_pixel(2, 2), Some(Color::BLUE));
+    /// ```
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
     #[must_use]
     pub fn pen(&mut self) -> Pen<'_, 'a> {
         Pen::new(self)
@@ -61,16 +148,66 @@ impl<'a> Canvas<'a> {
     }
 
     /// Sets the pixel at (x, y) of this [`Canvas`] to supplied color.
+    /// The coordinates can be of any [`Coord`] type.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.set_pixel(3, 4, Color::RED);
+    /// canvas.set_pixel(3usize, 5u32, Color::RED);
+    /// canvas.set_pixel(2.8f32, 6.2f32, Color::RED); // sets (3, 6)
+    /// ```
+    #[inline]
+    pub fn set_pixel(&mut self, x: impl Coord, y: impl Coord, color: impl Into<Color>) {
+        let (x, y) = (x.to_i32(), y.to_i32());
+        if 0 <= x && x < self.clamped_width && 0 <= y && y < self.clamped_height {
+            // SAFETY: idx is known to be positive and within bounds.
+            unsafe {
+                self.set_pixel_unchecked_raw_i32(x, y, u32::from(color.into()));
+            }
+        }
+    }
+
+    /// Sets the pixel at (x, y) of this [`Canvas`] to supplied color.
+    /// Returns whether the pixel was written, that is `false` if (x, y) is outside of the canvas.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    ///
+    /// assert!(canvas.try_set_pixel(3, 4, Color::RED));
+    /// assert!(!canvas.try_set_pixel(16, 4, Color::RED));
+    /// assert!(!canvas.try_set_pixel(-1, 4, Color::RED));
+    /// ```
     #[inline]
-    pub fn set_pixel(&mut self, x: i32, y: i32, color: impl Into<Color>) {
+    pub fn try_set_pixel(&mut self, x: impl Coord, y: impl Coord, color: impl Into<Color>) -> bool {
+        let (x, y) = (x.to_i32(), y.to_i32());
         if 0 <= x && x < self.clamped_width && 0 <= y && y < self.clamped_height {
             // SAFETY: idx is known to be positive and within bounds.
             unsafe {
                 self.set_pixel_unchecked_raw_i32(x, y, u32::from(color.into()));
             }
+            true
+        } else {
+            false
         }
     }
 
+    /// Sets the pixel nearest to (x, y) of this [`Canvas`] to supplied color.
+    /// Coordinates are rounded to the nearest integer, halfway cases away from zero (like [`f32::round`]).
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 16];
+    /// let mut canvas = Canvas::new(&mut buffer, 4, 4);
+    /// canvas.set_pixel_f32(1.5, 0.49, Color::RED);
+    ///
+    /// assert_eq!(buffer[2], Color::RED.0);
+    /// ```
+    #[inline]
+    pub fn set_pixel_f32(&mut self, x: f32, y: f32, color: impl Into<Color>) {
+        self.set_pixel(x, y, color);
+    }
+
     /// Sets the pixel at (x, y) of this [`Canvas`] to supplied color.
     /// # Safety
     /// x and y must be positive and smaller than canvas width and height respectively.
@@ -79,6 +216,45 @@ impl<'a> Canvas<'a> {
         self.set_pixel_unchecked_raw_i32(x, y, u32::from(color.into()));
     }
 
+    /// Returns the color of the pixel at (x, y) of this [`Canvas`], or `None` if (x, y) is outside of the canvas.
+    /// The coordinates can be of any [`Coord`] type.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.set_pixel(3, 4, Color::RED);
+    ///
+    /// assert_eq!(canvas.get_pixel(3, 4), Some(Color::RED));
+    /// assert_eq!(canvas.get_pixel(4, 4), Some(Color(0)));
+    /// assert_eq!(canvas.get_pixel(16, 4), None);
+    /// assert_eq!(canvas.get_pixel(3, -1), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn get_pixel(&self, x: impl Coord, y: impl Coord) -> Option<Color> {
+        let (x, y) = (x.to_i32(), y.to_i32());
+        if 0 <= x && x < self.clamped_width && 0 <= y && y < self.clamped_height {
+            // SAFETY: idx is known to be positive and within bounds.
+            Some(unsafe { self.get_pixel_unchecked(x, y) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the color of the pixel at (x, y) of this [`Canvas`].
+    /// # Safety
+    /// x and y must be positive and smaller than canvas width and height respectively.
+    #[allow(clippy::cast_sign_loss)]
+    #[inline]
+    #[must_use]
+    pub unsafe fn get_pixel_unchecked(&self, x: i32, y: i32) -> Color {
+        debug_assert!(x >= 0 && y >= 0);
+        let idx = y as usize * self.width + x as usize;
+
+        debug_assert!(idx < self.buffer.len());
+        Color(*self.buffer.get_unchecked(idx))
+    }
+
     /// Returns an iterator of pixels and their corresponding x and y coordinates.
     /// ```rust
     /// use vason::{Canvas, Color};
@@ -122,666 +298,1160 @@ impl<'a> Canvas<'a> {
             .map(|(i, p)| (i % self.width, i / self.width, p))
     }
 
-    /// Fills a rectangle shaped region in this [`Canvas`]. If width or height is <= 0 nothing is drawn.
-    /// ``` rust
+    /// Replaces the pixel at (x, y) of this [`Canvas`] with the result of `blend` applied to its current color.
+    /// Nothing happens if (x, y) is outside of the canvas.
+    /// Useful for custom blend modes and effects.
+    /// ```rust
     /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32; 256];
-    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.fill_rect(3, 3, 7, 7, Color::RED);
+    /// let mut buffer = [0u32; 4];
+    /// let mut canvas = Canvas::new(&mut buffer, 2, 2);
+    /// canvas.set_pixel(1, 0, Color::rgb(100, 50, 200));
+    ///
+    /// // keep only the red channel
+    /// canvas.blend_pixel_fn(1, 0, |c| {
+    ///     let (r, _, _) = c.to_rgb();
+    ///     Color::rgb(r, 0, 0)
+    /// });
+    ///
+    /// assert_eq!(buffer[1], Color::rgb(100, 0, 0).0);
     /// ```
     #[allow(clippy::cast_sign_loss)]
-    pub fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, color: impl Into<Color>) {
-        let raw_color = u32::from(color.into());
-        let (from_x, to_x, from_y, to_y) = self.clamp_rect_i32(x, x + w, y, y + h);
+    #[inline]
+    pub fn blend_pixel_fn(&mut self, x: impl Coord, y: impl Coord, blend: impl Fn(Color) -> Color) {
+        let (x, y) = (x.to_i32(), y.to_i32());
+        if 0 <= x && x < self.clamped_width && 0 <= y && y < self.clamped_height {
+            let pixel = &mut self.buffer[y as usize * self.width + x as usize];
+            *pixel = blend(Color(*pixel)).0;
+        }
+    }
 
-        let offset = from_y as usize * self.width;
-        let mut from_idx = offset + from_x as usize;
-        let mut to_idx = offset + to_x as usize;
+    /// Replaces every pixel of a rectangle shaped region in this [`Canvas`] with the result of `blend` applied to its current color.
+    /// The region is clipped to the canvas. If width or height is <= 0 nothing happens.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 16];
+    /// let mut canvas = Canvas::new(&mut buffer, 4, 4);
+    /// canvas.clear(Color::rgb(200, 100, 50));
+    ///
+    /// // darken the bottom right quarter (and beyond, which is clipped)
+    /// canvas.blend_rect_fn(2, 2, 10, 10, |c| {
+    ///     let (r, g, b) = c.to_rgb();
+    ///     Color::rgb(r / 2, g / 2, b / 2)
+    /// });
+    ///
+    /// assert_eq!(buffer[0], Color::rgb(200, 100, 50).0);
+    /// assert_eq!(buffer[15], Color::rgb(100, 50, 25).0);
+    /// ```
+    pub fn blend_rect_fn(
+        &mut self,
+        x: impl Coord,
+        y: impl Coord,
+        w: impl Coord,
+        h: impl Coord,
+        blend: impl Fn(Color) -> Color,
+    ) {
+        self.for_each_in_rect(x, y, w, h, |_, _, pixel| *pixel = blend(Color(*pixel)).0);
+    }
 
-        for _ in from_y..to_y {
-            self.buffer[from_idx..to_idx].fill(raw_color);
-            from_idx += self.width;
-            to_idx += self.width;
+    /// Calls `visit` with the coordinates of and a mutable reference to every pixel of a rectangle shaped region in this [`Canvas`], row by row.
+    /// The region is clipped exactly like [`fill_rect`](struct.Canvas.html#method.fill_rect) does, so pixels outside of the canvas are never visited.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 16];
+    /// let mut canvas = Canvas::new(&mut buffer, 4, 4);
+    ///
+    /// let mut visited = Vec::new();
+    /// canvas.for_each_in_rect(-1, 2, 3, 9, |x, y, pixel| {
+    ///     visited.push((x, y));
+    ///     *pixel = Color::RED.0;
+    /// });
+    ///
+    /// assert_eq!(visited, [(0, 2), (1, 2), (0, 3), (1, 3)]);
+    /// ```
+    #[allow(clippy::cast_sign_loss)]
+    pub fn for_each_in_rect(
+        &mut self,
+        x: impl Coord,
+        y: impl Coord,
+        w: impl Coord,
+        h: impl Coord,
+        mut visit: impl FnMut(usize, usize, &mut u32),
+    ) {
+        let (x, y, w, h) = (x.to_i32(), y.to_i32(), w.to_i32(), h.to_i32());
+        let (from_x, to_x, from_y, to_y) =
+            self.clamp_rect_i32(x, x.saturating_add(w), y, y.saturating_add(h));
+        let (from_x, to_x) = (from_x as usize, to_x as usize);
+
+        for y in from_y as usize..to_y as usize {
+            let offset = y * self.width;
+            for (x, pixel) in (from_x..).zip(&mut self.buffer[offset + from_x..offset + to_x]) {
+                visit(x, y, pixel);
+            }
         }
     }
 
-    /// Renders the outline of a rectangle shaped region in this [`Canvas`]. If width or height is <= 0 nothing is drawn.
-    /// ``` rust
+    /// Replaces every pixel of this [`Canvas`] that is exactly `from` with `to`.
+    /// ```rust
     /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32; 256];
-    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.outline_rect(3, 3, 7, 7, Color::RED);
+    /// let mut buffer = [0u32; 4];
+    /// let mut canvas = Canvas::new(&mut buffer, 2, 2);
+    /// canvas.set_pixel(1, 1, Color::RED);
+    /// canvas.replace_color(Color::RED, Color::BLUE);
+    ///
+    /// assert_eq!(buffer, [0, 0, 0, Color::BLUE.0]);
     /// ```
-    #[allow(clippy::cast_sign_loss)]
-    pub fn outline_rect(&mut self, x: i32, y: i32, w: i32, h: i32, color: impl Into<Color>) {
-        // consistency with fill_rect
-        if w <= 0 || h <= 0 {
-            return;
+    pub fn replace_color(&mut self, from: impl Into<Color>, to: impl Into<Color>) {
+        let (from, to) = (u32::from(from.into()), u32::from(to.into()));
+        for pixel in self.buffer.iter_mut().filter(|p| **p == from) {
+            *pixel = to;
         }
+    }
 
-        let raw_color = u32::from(color.into());
+    /// Replaces every pixel of this [`Canvas`] whose channels (alpha included) all differ by at most `tolerance` from `from` with `to`.
+    /// With a tolerance of 0 this is the same as [`replace_color`](struct.Canvas.html#method.replace_color).
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 3];
+    /// let mut canvas = Canvas::new(&mut buffer, 3, 1);
+    /// canvas.set_pixel(0, 0, Color::rgb(250, 5, 0));
+    /// canvas.set_pixel(1, 0, Color::rgb(200, 0, 0));
+    /// canvas.replace_color_tolerance(Color::RED, Color::BLUE, 10);
+    ///
+    /// assert_eq!(buffer, [Color::BLUE.0, Color::rgb(200, 0, 0).0, 0]);
+    /// ```
+    pub fn replace_color_tolerance(
+        &mut self,
+        from: impl Into<Color>,
+        to: impl Into<Color>,
+        tolerance: u8,
+    ) {
+        let (from, to) = (u32::from(from.into()), u32::from(to.into()));
+        for pixel in self
+            .buffer
+            .iter_mut()
+            .filter(|p| within_tolerance(**p, from, tolerance))
+        {
+            *pixel = to;
+        }
+    }
 
-        let x1 = x;
-        let x2 = x + w - 1;
-        let y1 = y;
-        let y2 = y + h - 1;
+    /// Replaces every pixel of this [`Canvas`] with the result of `f` applied to its color.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 4];
+    /// let mut canvas = Canvas::new(&mut buffer, 2, 2);
+    /// canvas.clear(Color::rgb(10, 20, 30));
+    ///
+    /// // swap red and blue
+    /// canvas.map_colors(|c| {
+    ///     let (r, g, b) = c.to_rgb();
+    ///     Color::rgb(b, g, r)
+    /// });
+    ///
+    /// assert_eq!(buffer[0], Color::rgb(30, 20, 10).0);
+    /// ```
+    pub fn map_colors(&mut self, f: impl Fn(Color) -> Color) {
+        for pixel in self.buffer.iter_mut() {
+            *pixel = f(Color(*pixel)).0;
+        }
+    }
 
-        if x2 >= 0 && y1 < self.clamped_height {
-            let from_x = x1.clamp(0, self.clamped_width - 1) as usize;
-            // draw the last pixel
-            let to_x = (x2 + 1).min(self.clamped_width) as usize;
+    /// Replaces every pixel of this [`Canvas`] with the result of `f` applied to its coordinates and color.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 4];
+    /// let mut canvas = Canvas::new(&mut buffer, 2, 2);
+    /// canvas.clear(Color::RED);
+    ///
+    /// // checkerboard
+    /// canvas.map(|x, y, c| if (x + y) % 2 == 0 { c } else { Color::BLACK });
+    ///
+    /// assert_eq!(buffer, [Color::RED.0, Color::BLACK.0, Color::BLACK.0, Color::RED.0]);
+    /// ```
+    pub fn map(&mut self, mut f: impl FnMut(usize, usize, Color) -> Color) {
+        for (x, y, pixel) in self.pixel_iter_mut() {
+            *pixel = f(x, y, Color(*pixel)).0;
+        }
+    }
 
-            if 0 <= y1 {
-                let offset = y1 as usize * self.width;
-                self.buffer[offset + from_x..offset + to_x].fill(raw_color);
-            }
+    /// Sets every pixel of this [`Canvas`] to the result of `f` applied to its coordinates, row by row.
+    /// Unlike [`map`](struct.Canvas.html#method.map) the current colors are not read, which makes it ideal for gradients, patterns and test images.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256 * 4];
+    /// let mut canvas = Canvas::new(&mut buffer, 256, 4);
+    ///
+    /// // horizontal gradient
+    /// canvas.fill_with(|x, _| Color::gray(x as u8));
+    ///
+    /// assert_eq!(buffer[2 * 256 + 100], Color::gray(100).0);
+    /// ```
+    pub fn fill_with(&mut self, mut f: impl FnMut(usize, usize) -> Color) {
+        if self.width == 0 {
+            return;
+        }
 
-            if 0 <= y2 && y2 < self.clamped_height {
-                let offset = y2 as usize * self.width;
-                self.buffer[offset + from_x..offset + to_x].fill(raw_color);
+        for (y, row) in self.buffer.chunks_exact_mut(self.width).enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = f(x, y).0;
             }
         }
+    }
 
-        if y2 >= 0 && x1 < self.clamped_width {
-            let from_y = y1.clamp(0, self.clamped_height - 1);
-            let to_y = y2.min(self.clamped_height);
-
-            if 0 <= x1 {
-                for j in from_y..to_y {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x1, j, raw_color);
-                    }
-                }
-            }
+    /// Calls `f` with the coordinates and color of every pixel of this [`Canvas`], row by row.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 4];
+    /// let mut canvas = Canvas::new(&mut buffer, 2, 2);
+    /// canvas.set_pixel(1, 1, Color::RED);
+    ///
+    /// let mut red = Vec::new();
+    /// canvas.for_each(|x, y, c| if c == Color::RED { red.push((x, y)) });
+    ///
+    /// assert_eq!(red, [(1, 1)]);
+    /// ```
+    pub fn for_each(&self, mut f: impl FnMut(usize, usize, Color)) {
+        for (x, y, pixel) in self.pixel_iter() {
+            f(x, y, Color(pixel));
+        }
+    }
 
-            if 0 <= x2 && x2 < self.clamped_width {
-                for j in from_y..to_y {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x2, j, raw_color);
-                    }
-                }
-            }
+    /// Replaces every pixel of this [`Canvas`] with `above` if its [`luminance`](Color::luminance) is at least `level`, otherwise with `below`.
+    /// A level of 0 turns every pixel into `above`, a level of 255 only keeps pure white as `above`.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 3];
+    /// let mut canvas = Canvas::new(&mut buffer, 3, 1);
+    /// canvas.set_pixel(0, 0, Color::gray(100));
+    /// canvas.set_pixel(1, 0, Color::YELLOW);
+    /// canvas.set_pixel(2, 0, Color::BLUE);
+    /// canvas.threshold(128, Color::BLACK, Color::WHITE);
+    ///
+    /// assert_eq!(buffer, [Color::BLACK.0, Color::WHITE.0, Color::BLACK.0]);
+    /// ```
+    pub fn threshold(&mut self, level: u8, below: impl Into<Color>, above: impl Into<Color>) {
+        let (below, above) = (u32::from(below.into()), u32::from(above.into()));
+        for pixel in self.buffer.iter_mut() {
+            *pixel = if Color(*pixel).luminance() >= level {
+                above
+            } else {
+                below
+            };
         }
     }
 
-    /// Renders the outline of a rectangle shaped region with a given thickness in this [`Canvas`]. If the width, height or thickness is <= 0 nothing is drawn.
-    /// ``` rust
+    /// Blurs this [`Canvas`] by averaging every pixel with its neighbours within `radius` (a (2 * radius + 1)² box).
+    /// The blur is separable and uses a running sum, so its cost doesn't depend on the radius.
+    /// Pixels outside of the canvas are treated as copies of the nearest edge pixel.
+    /// Averaging happens in linear light (see [`Color::to_linear`]). A radius of 0 does nothing.
+    /// ```rust
     /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32; 256];
-    /// let mut canvas = Canvas::new(&mut buffer, 16,16);
-    /// canvas.thick_outline_rect(3, 3, 7, 7, 2, Color::RED);
+    /// let mut buffer = [0u32; 9];
+    /// let mut canvas = Canvas::new(&mut buffer, 3, 3);
+    /// canvas.set_pixel(1, 1, Color::WHITE);
+    /// canvas.box_blur(1);
+    ///
+    /// // every pixel averages the same 3x3 window (edges are clamped), so they all end up equal
+    /// assert!(buffer.iter().all(|p| *p == buffer[0]));
+    /// assert_ne!(buffer[0], Color::BLACK.0);
     /// ```
-    #[allow(clippy::cast_sign_loss)]
-    pub fn thick_outline_rect(
-        &mut self,
-        x: i32,
-        y: i32,
-        w: i32,
-        h: i32,
-        thickness: i32,
-        color: impl Into<Color>,
-    ) {
-        // consistency with fill_rect
-        if w <= 0 || h <= 0 || thickness <= 0 {
-            return;
-        } else if thickness == 1 {
-            self.outline_rect(x, y, w, h, color);
+    pub fn box_blur(&mut self, radius: u32) {
+        if radius == 0 || self.buffer.is_empty() {
             return;
         }
 
-        let raw_color = u32::from(color.into());
-
-        let x1 = x;
-        let x2 = x + w;
-        let y1 = y;
-        let y2 = y + h;
+        let mut pixels = self.to_linear_pixels();
+        box_blur_lines(&mut pixels, self.height, self.width, self.width, 1, radius);
+        box_blur_lines(&mut pixels, self.width, 1, self.height, self.width, radius);
+        self.set_linear_pixels(&pixels);
+    }
 
-        let half_thickness = thickness / 2;
+    /// Blurs this [`Canvas`] with an approximate gaussian of standard deviation `sigma`, made of three successive box blurs.
+    /// Like [`box_blur`](struct.Canvas.html#method.box_blur) it clamps at the edges, works in linear light
+    /// and its cost doesn't depend on `sigma`. If `sigma` is not positive nothing happens.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 81];
+    /// let mut canvas = Canvas::new(&mut buffer, 9, 9);
+    /// canvas.set_pixel(4, 4, Color::WHITE);
+    /// canvas.gaussian_blur(1.5);
+    ///
+    /// // brightest in the middle, fading out towards the edges
+    /// let (center, _, _) = Color(buffer[4 * 9 + 4]).to_rgb();
+    /// let (near, _, _) = Color(buffer[4 * 9 + 5]).to_rgb();
+    /// let (far, _, _) = Color(buffer[4 * 9 + 8]).to_rgb();
+    /// assert!(center > near && near > far);
+    /// ```
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn gaussian_blur(&mut self, sigma: f32) {
+        if sigma.is_nan() || sigma <= 0.0 || self.buffer.is_empty() {
+            return;
+        }
 
-        if x2 + half_thickness >= 0 && y1 - half_thickness < self.clamped_height {
-            let from_x = (x1 - half_thickness).clamp(0, self.clamped_width - 1) as usize;
-            let to_x = (x2 + half_thickness).min(self.clamped_width) as usize;
+        // box sizes whose combined variance is closest to sigma² (http://blog.ivank.net/fastest-gaussian-blur.html)
+        let variance = 12.0 * f64::from(sigma).powi(2);
+        let ideal = (variance / 3.0 + 1.0).sqrt();
+        let mut lower = ideal.floor();
+        if lower % 2.0 == 0.0 {
+            lower -= 1.0;
+        }
+        let lower_count =
+            ((variance - 3.0 * lower * lower - 12.0 * lower - 9.0) / (-4.0 * lower - 4.0)).round();
 
-            if 0 <= y1 + half_thickness {
-                for j in
-                    (y1 - half_thickness).max(0)..(y1 + half_thickness).min(self.clamped_height)
-                {
-                    let offset = j as usize * self.width;
-                    self.buffer[offset + from_x..offset + to_x].fill(raw_color);
-                }
+        let mut pixels = self.to_linear_pixels();
+        for pass in 0..3 {
+            let size = if f64::from(pass) < lower_count {
+                lower
+            } else {
+                lower + 2.0
+            };
+            let radius = ((size - 1.0) / 2.0).min(f64::from(u32::MAX)) as u32;
+            if radius > 0 {
+                box_blur_lines(&mut pixels, self.height, self.width, self.width, 1, radius);
+                box_blur_lines(&mut pixels, self.width, 1, self.height, self.width, radius);
             }
+        }
+        self.set_linear_pixels(&pixels);
+    }
 
-            if 0 <= y2 + half_thickness && y2 - half_thickness < self.clamped_height {
-                for j in
-                    (y2 - half_thickness).max(0)..(y2 + half_thickness).min(self.clamped_height)
-                {
-                    let offset = j as usize * self.width;
-                    self.buffer[offset + from_x..offset + to_x].fill(raw_color);
-                }
-            }
+    /// Sharpens this [`Canvas`] with a 3x3 kernel: every pixel is pushed away from the average of its 4 direct neighbours by `amount`.
+    /// This is a [`convolve3x3`](struct.Canvas.html#method.convolve3x3) with the classic sharpen kernel scaled by `amount`.
+    /// An amount of 0 leaves the image unchanged.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 3];
+    /// let mut canvas = Canvas::new(&mut buffer, 3, 1);
+    /// canvas.set_pixel(0, 0, Color::gray(100));
+    /// canvas.set_pixel(1, 0, Color::gray(120));
+    /// canvas.set_pixel(2, 0, Color::gray(100));
+    /// canvas.sharpen(1.0);
+    ///
+    /// assert_eq!(buffer, [Color::gray(80).0, Color::gray(160).0, Color::gray(80).0]);
+    /// ```
+    pub fn sharpen(&mut self, amount: f32) {
+        if amount == 0.0 {
+            return;
         }
 
-        if y2 + half_thickness >= 0 && x1 - half_thickness < self.clamped_width {
-            let from_y = y1.clamp(0, self.clamped_height - 1);
-            let to_y = y2.min(self.clamped_height);
+        let kernel = [
+            [0.0, -amount, 0.0],
+            [-amount, 1.0 + 4.0 * amount, -amount],
+            [0.0, -amount, 0.0],
+        ];
+        self.convolve3x3(kernel, 1.0, 0.0);
+    }
+
+    /// Convolves this [`Canvas`] with a 3x3 kernel: every r, g and b channel becomes the kernel weighted sum of the
+    /// 3x3 neighbourhood around it, divided by `divisor` and offset by `bias`, clamped to 0..=255. Alpha is kept.
+    /// The neighbourhood is always read from the original image, border pixels reuse the nearest edge pixel for missing neighbours.
+    /// A divisor of 0 is treated as 1.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 9];
+    /// let mut canvas = Canvas::new(&mut buffer, 3, 3);
+    /// canvas.clear(Color::BLACK);
+    /// canvas.set_pixel(1, 1, Color::gray(90));
+    ///
+    /// // blur
+    /// canvas.convolve3x3([[1.0, 1.0, 1.0], [1.0, 1.0, 1.0], [1.0, 1.0, 1.0]], 9.0, 0.0);
+    /// assert!(canvas.buffer().iter().all(|p| *p == Color::gray(10).0));
+    ///
+    /// // emboss
+    /// canvas.convolve3x3([[-2.0, -1.0, 0.0], [-1.0, 1.0, 1.0], [0.0, 1.0, 2.0]], 1.0, 128.0);
+    /// ```
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn convolve3x3(&mut self, kernel: [[f32; 3]; 3], divisor: f32, bias: f32) {
+        if self.buffer.is_empty() {
+            return;
+        }
 
-            if 0 <= x1 + half_thickness {
-                for j in from_y..to_y {
-                    for i in
-                        (x1 - half_thickness).max(0)..(x1 + half_thickness).min(self.clamped_width)
-                    {
-                        unsafe {
-                            self.set_pixel_unchecked_raw_i32(i, j, raw_color);
+        let divisor = if divisor == 0.0 { 1.0 } else { divisor };
+        let source = self.buffer.to_vec();
+        let (width, height) = (self.width, self.height);
+
+        for y in 0..height {
+            let rows = [y.saturating_sub(1), y, (y + 1).min(height - 1)].map(|r| r * width);
+            for x in 0..width {
+                let cols = [x.saturating_sub(1), x, (x + 1).min(width - 1)];
+
+                let mut sums = [0.0f32; 3];
+                for (row, weights) in rows.iter().zip(kernel.iter()) {
+                    for (col, weight) in cols.iter().zip(weights.iter()) {
+                        let bytes = source[row + col].to_le_bytes();
+                        for (sum, byte) in sums.iter_mut().zip(bytes.iter()) {
+                            *sum += weight * f32::from(*byte);
                         }
                     }
                 }
-            }
 
-            if 0 <= x2 + half_thickness && x2 - half_thickness < self.clamped_width {
-                for j in from_y..to_y {
-                    for i in
-                        (x2 - half_thickness).max(0)..(x2 + half_thickness).min(self.clamped_width)
-                    {
-                        unsafe {
-                            self.set_pixel_unchecked_raw_i32(i, j, raw_color);
-                        }
-                    }
+                let mut out = source[rows[1] + x].to_le_bytes();
+                for (channel, sum) in out.iter_mut().zip(sums.iter()) {
+                    *channel = (sum / divisor + bias).round().clamp(0.0, 255.0) as u8;
                 }
+                self.buffer[rows[1] + x] = u32::from_le_bytes(out);
             }
         }
     }
 
-    /// Fills a circle shaped region in this [`Canvas`]. The radius must be positive.
-    /// ``` rust
+    /// Adds `delta` to the r, g and b channels of every pixel of this [`Canvas`], saturating at 0 and 255. Alpha is kept.
+    /// ```rust
     /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32; 256];
-    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.fill_circle(8, 8, 4, Color::GREEN);
+    /// let mut buffer = [0u32; 2];
+    /// let mut canvas = Canvas::new(&mut buffer, 2, 1);
+    /// canvas.set_pixel(0, 0, Color::rgb(10, 100, 250));
+    /// canvas.adjust_brightness(20);
+    ///
+    /// assert_eq!(buffer[0], Color::rgb(30, 120, 255).0);
     /// ```
-    #[allow(clippy::cast_sign_loss, clippy::many_single_char_names)]
-    pub fn fill_circle(&mut self, x: i32, y: i32, mut r: i32, color: impl Into<Color>) {
-        if r < 1 {
-            return;
-        }
+    pub fn adjust_brightness(&mut self, delta: i32) {
+        self.adjust(delta, 1.0);
+    }
 
-        let raw_color = u32::from(color.into());
+    /// Scales the r, g and b channels of every pixel of this [`Canvas`] by `factor` around the 128 midpoint, saturating at 0 and 255. Alpha is kept.
+    /// A factor of 1 leaves the image unchanged, 0 makes it uniformly gray, and a negative one inverts it.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 2];
+    /// let mut canvas = Canvas::new(&mut buffer, 2, 1);
+    /// canvas.set_pixel(0, 0, Color::rgb(28, 128, 200));
+    /// canvas.adjust_contrast(2.0);
+    ///
+    /// assert_eq!(buffer[0], Color::rgb(0, 128, 255).0);
+    /// ```
+    pub fn adjust_contrast(&mut self, factor: f32) {
+        self.adjust(0, factor);
+    }
 
-        let mut i = -r;
-        let mut j = 0;
-        let mut err = 2 - 2 * r;
-        loop {
-            let y1 = y - j;
-            let y2 = y + j;
-            //i is negative
-            let from_x = (x + i).clamp(0, self.clamped_width - 1);
-            let to_x = (x - i).clamp(from_x, self.clamped_width);
+    /// Adjusts contrast (see [`adjust_contrast`](struct.Canvas.html#method.adjust_contrast)) and then brightness
+    /// (see [`adjust_brightness`](struct.Canvas.html#method.adjust_brightness)) in a single pass, saturating only once at the end.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 1];
+    /// let mut canvas = Canvas::new(&mut buffer, 1, 1);
+    /// canvas.set_pixel(0, 0, Color::gray(200));
+    /// canvas.adjust(-100, 2.0);
+    ///
+    /// assert_eq!(buffer[0], Color::gray(172).0);
+    /// ```
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn adjust(&mut self, brightness: i32, contrast: f32) {
+        let mut lut = [0u8; 256];
+        for (c, value) in (0u8..=255).zip(lut.iter_mut()) {
+            let adjusted = (f32::from(c) - 128.0) * contrast + 128.0;
+            *value = (f64::from(adjusted.round()) + f64::from(brightness)).clamp(0.0, 255.0) as u8;
+        }
 
-            if 0 <= y1 && y1 < self.clamped_height {
-                let offset = y1 as usize * self.width;
-                let range = offset + from_x as usize..offset + to_x as usize;
-                self.buffer[range].fill(raw_color);
-            }
+        for pixel in self.buffer.iter_mut() {
+            let (r, g, b, a) = PixelFormat::unpack(*pixel);
+            *pixel = PixelFormat::pack(
+                lut[usize::from(r)],
+                lut[usize::from(g)],
+                lut[usize::from(b)],
+                a,
+            );
+        }
+    }
 
-            if 0 <= y2 && y2 < self.clamped_height {
-                let offset = y2 as usize * self.width;
-                let range = offset + from_x as usize..offset + to_x as usize;
-                self.buffer[range].fill(raw_color);
-            }
-            r = err;
-            if r <= j {
-                j += 1;
-                err += j * 2 + 1;
-            }
-            if r > i || err > j {
-                i += 1;
-                err += i * 2 + 1;
-            }
+    /// Inverts the pixels of a rectangle shaped region in this [`Canvas`] (see [`Color::invert`]), e.g. to highlight a selection.
+    /// The region is clipped exactly like [`fill_rect`](struct.Canvas.html#method.fill_rect) does. Inverting the same region twice restores it.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 4];
+    /// let mut canvas = Canvas::new(&mut buffer, 2, 2);
+    /// canvas.clear(Color::RED);
+    /// canvas.invert_rect(1, 0, 5, 5);
+    ///
+    /// assert_eq!(buffer, [Color::RED.0, Color::CYAN.0, Color::RED.0, Color::CYAN.0]);
+    /// ```
+    pub fn invert_rect(&mut self, x: impl Coord, y: impl Coord, w: impl Coord, h: impl Coord) {
+        self.blend_rect_fn(x, y, w, h, Color::invert);
+    }
 
-            if i >= 0 {
-                break;
-            }
-        }
+    /// Inverts every pixel of this [`Canvas`] (see [`Color::invert`]).
+    pub fn invert(&mut self) {
+        self.map_colors(Color::invert);
     }
 
-    /// Renders the outline of a circle shaped region in this [`Canvas`]. The radius must be positive,
-    /// ``` rust
+    /// Replaces this [`Canvas`] with its edge map: the gradient magnitude of the [`luminance`](Color::luminance),
+    /// computed with the Sobel operators and written as opaque gray (clamped to 255).
+    /// Border pixels reuse the nearest edge pixel for missing neighbours, so a flat image becomes black.
+    /// ```rust
     /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32; 256];
-    /// let mut canvas = Canvas::new(&mut buffer, 16,16);
-    /// canvas.outline_circle(8, 8, 8, Color::YELLOW);
+    /// let mut buffer = [0u32; 16];
+    /// let mut canvas = Canvas::new(&mut buffer, 4, 4);
+    /// canvas.fill_rect(2, 0, 2, 4, Color::WHITE);
+    /// canvas.sobel_edges();
+    ///
+    /// assert_eq!(&buffer[..4], &[Color::BLACK.0, Color::WHITE.0, Color::WHITE.0, Color::BLACK.0]);
     /// ```
-    #[allow(clippy::cast_sign_loss, clippy::many_single_char_names)]
-    pub fn outline_circle(&mut self, x: i32, y: i32, mut r: i32, color: impl Into<Color>) {
-        if r < 1 {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn sobel_edges(&mut self) {
+        if self.buffer.is_empty() {
             return;
         }
 
-        let raw_color = u32::from(color.into());
-        let mut i = -r;
-        let mut j = 0;
-        let mut err = 2 - 2 * r;
-        loop {
-            let x1 = x - i;
-            let x2 = x + i;
-            let y1 = y - j;
-            let y2 = y + j;
+        let luminance: Vec<i32> = self
+            .buffer
+            .iter()
+            .map(|p| i32::from(Color(*p).luminance()))
+            .collect();
+        let (width, height) = (self.width, self.height);
 
-            // TODO: benchmark this with precise tooling against just using self.set_pixel()
-            // flamegraph shows a siginificant difference, but I'm not convinced.
-            if 0 <= x1 && x1 < self.clamped_width {
-                if 0 <= y1 && y1 < self.clamped_height {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x1, y1, raw_color);
-                    }
-                }
-                if 0 <= y2 && y2 < self.clamped_height {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x1, y2, raw_color);
-                    }
-                }
-            }
-            if 0 <= x2 && x2 < self.clamped_width {
-                if 0 <= y1 && y1 < self.clamped_height {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x2, y1, raw_color);
-                    }
-                }
-                if 0 <= y2 && y2 < self.clamped_height {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x2, y2, raw_color);
-                    }
-                }
-            }
+        for y in 0..height {
+            let rows = [y.saturating_sub(1), y, (y + 1).min(height - 1)].map(|r| r * width);
+            for x in 0..width {
+                let cols = [x.saturating_sub(1), x, (x + 1).min(width - 1)];
+                let at = |r: usize, c: usize| luminance[rows[r] + cols[c]];
 
-            r = err;
-            if r <= j {
-                j += 1;
-                err += j * 2 + 1;
-            }
-            if r > i || err > j {
-                i += 1;
-                err += i * 2 + 1;
-            }
+                let gx = at(0, 2) + 2 * at(1, 2) + at(2, 2) - at(0, 0) - 2 * at(1, 0) - at(2, 0);
+                let gy = at(2, 0) + 2 * at(2, 1) + at(2, 2) - at(0, 0) - 2 * at(0, 1) - at(0, 2);
+                let magnitude = f64::from(gx * gx + gy * gy).sqrt().min(255.0);
 
-            if i >= 0 {
-                break;
+                self.buffer[rows[1] + x] = Color::gray(magnitude as u8).0;
             }
         }
     }
 
-    /// Renders the outline of a circle shaped region with a given thickness in this [`Canvas`]. The radius must be positive.
-    /// The stroke witdth grows symmetrically (inwards and outwards), that is the supplied radius will be the center of the stroke.
-    /// ``` rust
-    /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32; 256];
-    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.thick_outline_circle(4, 8, 8, 2, Color::CYAN);
+    /// Fills this [`Canvas`] with a smooth, colorful plasma pattern made of overlapping sine waves, mapped to hues.
+    /// `scale` is roughly the size of the blobs in pixels, `seed` shifts the phases and the palette.
+    /// The same seed and scale always produce the same image. If `scale` is not positive nothing happens.
+    /// ```rust
+    /// use vason::Canvas;
+    /// let mut buffer = [0u32; 64 * 64];
+    /// let mut canvas = Canvas::new(&mut buffer, 64, 64);
+    /// canvas.fill_plasma(42, 16.0);
+    /// let hash = canvas.content_hash();
+    ///
+    /// canvas.fill_plasma(42, 16.0);
+    /// assert_eq!(canvas.content_hash(), hash);
     /// ```
-    #[allow(clippy::similar_names)]
-    pub fn thick_outline_circle(
-        &mut self,
-        x: i32,
-        y: i32,
-        r: i32,
-        thickness: i32,
-        color: impl Into<Color>,
-    ) {
-        if thickness <= 0 || r < 1 {
-            return;
-        } else if thickness == 1 {
-            self.outline_circle(x, y, r, color);
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fill_plasma(&mut self, seed: u64, scale: f32) {
+        if scale.is_nan() || scale <= 0.0 {
             return;
         }
 
-        let raw_color = u32::from(color.into());
-
-        let half_thickness = thickness / 2;
+        let mut state = seed;
+        let mut phase =
+            || (splitmix64(&mut state) >> 40) as f32 / (1u64 << 24) as f32 * std::f32::consts::TAU;
+        let phases = [phase(), phase(), phase(), phase(), phase()];
+        let frequency = 1.0 / scale;
+
+        self.fill_with(|x, y| {
+            let (x, y) = (x as f32 * frequency, y as f32 * frequency);
+            let value = (x + phases[0]).sin()
+                + (y + phases[1]).sin()
+                + ((x + y) * 0.5 + phases[2]).sin()
+                + ((x * x + y * y).sqrt() + phases[3]).sin();
+
+            // value is in -4.0..=4.0, spread it over the whole hue circle
+            let hue = value * 45.0 + phases[4].to_degrees();
+            Color::from_hsv(hue, 1.0, 1.0)
+        });
+    }
 
-        let ro = r + half_thickness;
-        let ri = ro - thickness + 1;
+    /// Fills this [`Canvas`] with 2D Perlin (gradient) noise, mapped between `from` and `to` with [`Color::lerp`].
+    /// `frequency` is the number of noise cells per pixel, so smaller values give larger features (0.05 gives features of ~20 pixels).
+    /// The same seed and frequency always produce the same image.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 64 * 64];
+    /// let mut canvas = Canvas::new(&mut buffer, 64, 64);
+    /// canvas.fill_noise(7, 0.05, Color::DARK_BLUE, Color::SKY_BLUE);
+    /// let hash = canvas.content_hash();
+    ///
+    /// canvas.fill_noise(7, 0.05, Color::DARK_BLUE, Color::SKY_BLUE);
+    /// assert_eq!(canvas.content_hash(), hash);
+    /// ```
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fill_noise(
+        &mut self,
+        seed: u64,
+        frequency: f32,
+        from: impl Into<Color>,
+        to: impl Into<Color>,
+    ) {
+        let (from, to) = (from.into(), to.into());
+        let noise = PerlinNoise::new(seed);
 
-        let mut xo = ro;
-        let mut xi = ri;
-        let mut j = 0;
-        let mut erro = 1 - xo;
-        let mut erri = 1 - xi;
+        self.fill_with(|x, y| {
+            let value = noise.sample(x as f32 * frequency, y as f32 * frequency);
+            from.lerp(to, value * 0.5 + 0.5)
+        });
+    }
 
-        while xo >= j {
-            // TODO: inline these calls manually to do fewer checks.
-            self.hline(y + j, x + xi, x + xo, raw_color);
-            self.vline(x + j, y + xi, y + xo, raw_color);
-            self.hline(y + j, x - xo, x - xi, raw_color);
-            self.vline(x - j, y + xi, y + xo, raw_color);
-            self.hline(y - j, x - xo, x - xi, raw_color);
-            self.vline(x - j, y - xo, y - xi, raw_color);
-            self.hline(y - j, x + xi, x + xo, raw_color);
-            self.vline(x + j, y - xo, y - xi, raw_color);
+    /// Colors every pixel of this [`Canvas`] with the color of its nearest site (by euclidean distance), giving a Voronoi diagram.
+    /// Ties go to the site that comes first in `sites`. If `sites` is empty nothing happens.
+    /// Every pixel is compared against every site, so the cost is O(width * height * sites): fine for a few dozen sites.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 4];
+    /// let mut canvas = Canvas::new(&mut buffer, 4, 1);
+    /// canvas.fill_voronoi(&[(0, 0, Color::RED), (3, 0, Color::BLUE)]);
+    ///
+    /// assert_eq!(buffer, [Color::RED.0, Color::RED.0, Color::BLUE.0, Color::BLUE.0]);
+    /// ```
+    pub fn fill_voronoi(&mut self, sites: &[(i32, i32, Color)]) {
+        if sites.is_empty() {
+            return;
+        }
 
-            j += 1;
+        for (x, y, pixel) in self.pixel_iter_mut() {
+            let (x, y) = (x as i128, y as i128);
+            let (mut nearest, mut color) = (i128::MAX, sites[0].2);
 
-            if erro < 0 {
-                erro += 2 * j + 1;
-            } else {
-                xo -= 1;
-                erro += 2 * (j - xo) + 1;
+            for &(sx, sy, site_color) in sites {
+                let (dx, dy) = (i128::from(sx) - x, i128::from(sy) - y);
+                let distance = dx * dx + dy * dy;
+                if distance < nearest {
+                    nearest = distance;
+                    color = site_color;
+                }
             }
 
-            if j > ri {
-                xi = j;
-            } else if erri < 0 {
-                erri += 2 * j + 1;
-            } else {
-                xi -= 1;
-                erri += 2 * (j - xi) + 1;
-            }
+            *pixel = color.0;
         }
     }
 
-    /// Fills an ellipse shaped region in this [`Canvas`]. The radii must be positive.
-    /// ``` rust
+    /// Returns a smaller copy of this [`Canvas`] as a `new_w * new_h` buffer, where every pixel is the area-weighted average
+    /// (in linear light, see [`Color::to_linear`]) of the pixels it covers. Looks much better than nearest neighbour sampling, e.g. for thumbnails.
+    /// The new dimensions are clamped to the dimensions of this canvas, so it never upscales.
+    /// ```rust
     /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32; 256];
-    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.fill_ellipse(8, 8, 8, 4, Color::RED);
+    /// let mut buffer = [0u32; 16];
+    /// let mut canvas = Canvas::new(&mut buffer, 4, 4);
+    /// canvas.clear(Color::BLACK);
+    /// canvas.fill_rect(0, 0, 2, 2, Color::RED);
+    ///
+    /// let small = canvas.downscale_box(2, 2);
+    /// assert_eq!(small, [Color::RED.0, Color::BLACK.0, Color::BLACK.0, Color::BLACK.0]);
+    ///
+    /// // a quarter of red's light, which is brighter than a quarter of 255
+    /// assert_eq!(canvas.downscale_box(1, 1), [Color::rgb(137, 0, 0).0]);
     /// ```
-    #[allow(clippy::many_single_char_names, clippy::cast_sign_loss)]
-    pub fn fill_ellipse(&mut self, x: i32, y: i32, a: i32, b: i32, color: impl Into<Color>) {
-        if a < 1 || b < 1 {
-            return;
+    #[must_use]
+    pub fn downscale_box(&self, new_w: usize, new_h: usize) -> Vec<u32> {
+        let (new_w, new_h) = (new_w.min(self.width), new_h.min(self.height));
+        if new_w == 0 || new_h == 0 {
+            return Vec::new();
         }
 
-        let raw_color = u32::from(color.into());
-
-        let mut i = -a;
-        let mut j = 0;
-
-        // change to larger integers to avoid overflow.
-        let b2 = i64::from(b) * i64::from(b);
-        let a2 = i64::from(a) * i64::from(a);
-        let mut err = i64::from(i) * (2 * b2 + i64::from(i)) + b2;
-
-        loop {
-            let y1 = y - j;
-            let y2 = y + j;
-            //i is non-positive
-            let from_x = (x + i).clamp(0, self.clamped_width - 1);
-            let to_x = (x - i).clamp(from_x, self.clamped_width);
-
-            if 0 <= y1 && y1 < self.clamped_height {
-                let offset = y1 as usize * self.width;
-                let range = offset + from_x as usize..offset + to_x as usize;
-                self.buffer[range].fill(raw_color);
-            }
-
-            if 0 <= y2 && y2 < self.clamped_height {
-                let offset = y2 as usize * self.width;
-                let range = offset + from_x as usize..offset + to_x as usize;
-                self.buffer[range].fill(raw_color);
-            }
-
-            let e2 = 2 * err;
-            if e2 >= i64::from(i * 2 + 1) * b2 {
-                i += 1;
-                err += i64::from(i * 2 + 1) * b2;
-            }
+        let pixels = self.to_linear_pixels();
+        let columns = box_weights(self.width, new_w);
+        let rows = box_weights(self.height, new_h);
 
-            if e2 <= i64::from(j * 2 + 1) * a2 {
-                j += 1;
-                err += i64::from(j * 2 + 1) * a2;
+        // horizontal pass: new_w * height
+        let mut narrow = Vec::with_capacity(new_w * self.height);
+        for row in pixels.chunks_exact(self.width) {
+            for weights in &columns {
+                narrow.push(weighted_sum(weights.iter().map(|&(x, w)| (row[x], w))));
             }
+        }
 
-            if i > 0 {
-                break;
+        // vertical pass: new_w * new_h
+        let mut result = Vec::with_capacity(new_w * new_h);
+        for weights in &rows {
+            for x in 0..new_w {
+                let [r, g, b, a] =
+                    weighted_sum(weights.iter().map(|&(y, w)| (narrow[y * new_w + x], w)));
+                result.push(Color::from_linear(r, g, b, a).0);
             }
         }
 
-        while j < b {
-            j += 1;
-            if 0 <= x && x < self.clamped_width {
-                let y1 = y + j;
-                let y2 = y - j;
-                if 0 <= y1 && y1 < self.clamped_height {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x, y1, raw_color);
-                    }
+        result
+    }
+
+    /// Returns a copy of this [`Canvas`] enlarged by an integer `factor`, every pixel becoming a `factor` × `factor` block,
+    /// which keeps pixel art crisp. Returns the buffer together with its width and height, ready for [`Canvas::new`].
+    /// A factor of 0 gives an empty buffer, 1 gives a plain copy.
+    /// # Panics
+    /// This function panics if the size of the result does not fit in a `usize`.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [Color::RED.0, Color::BLUE.0];
+    /// let canvas = Canvas::new(&mut buffer, 2, 1);
+    ///
+    /// let (mut big, width, height) = canvas.upscale_integer(2);
+    /// assert_eq!((width, height), (4, 2));
+    /// let (r, b) = (Color::RED.0, Color::BLUE.0);
+    /// assert_eq!(big, [r, r, b, b, r, r, b, b]);
+    ///
+    /// let big = Canvas::new(&mut big, width, height);
+    /// assert_eq!(big.width(), 4);
+    /// ```
+    #[must_use]
+    pub fn upscale_integer(&self, factor: u32) -> (Vec<u32>, usize, usize) {
+        let factor = usize::try_from(factor).expect("factor does not fit in a usize");
+        let new_width = self
+            .width
+            .checked_mul(factor)
+            .expect("upscaled width overflows usize");
+        let new_height = self
+            .height
+            .checked_mul(factor)
+            .expect("upscaled height overflows usize");
+        new_width
+            .checked_mul(new_height)
+            .expect("upscaled size overflows usize");
+
+        let mut result = Vec::with_capacity(new_width * new_height);
+        if new_width > 0 {
+            for row in self.buffer.chunks_exact(self.width) {
+                let start = result.len();
+                for pixel in row {
+                    result.extend(std::iter::repeat(*pixel).take(factor));
                 }
-                if 0 <= y2 && y2 < self.clamped_height {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x, y2, raw_color);
-                    }
+                for _ in 1..factor {
+                    result.extend_from_within(start..start + new_width);
                 }
             }
         }
+
+        (result, new_width, new_height)
     }
 
-    /// Renders the outline of an ellipse shaped region in this [`Canvas`]. The radii must be positive.
-    /// ``` rust
+    /// Copies the rectangle `src_rect` (given as (x, y, w, h)) of `src` onto this [`Canvas`], with its top left corner at (dst_x, dst_y).
+    /// Useful for sprite sheets, where many frames share one buffer. The source rectangle is clipped to `src`,
+    /// and the destination to this canvas, so only pixels present in both are copied. If the source rectangle is empty nothing happens.
+    /// ```rust
     /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32; 256];
-    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.outline_ellipse(8, 8, 8, 4, Color::RED);
+    /// // a sprite sheet of two 2x2 frames
+    /// let mut sheet = [0u32; 4 * 2];
+    /// let mut sheet = Canvas::new(&mut sheet, 4, 2);
+    /// sheet.fill_rect(0, 0, 2, 2, Color::RED);
+    /// sheet.fill_rect(2, 0, 2, 2, Color::BLUE);
+    ///
+    /// let mut buffer = [0u32; 9];
+    /// let mut canvas = Canvas::new(&mut buffer, 3, 3);
+    /// canvas.blit_region(&sheet, (2, 0, 2, 2), 1, 1);
+    ///
+    /// let b = Color::BLUE.0;
+    /// assert_eq!(buffer, [0, 0, 0, 0, b, b, 0, b, b]);
     /// ```
-    #[allow(clippy::many_single_char_names)]
-    pub fn outline_ellipse(&mut self, x: i32, y: i32, a: i32, b: i32, color: impl Into<Color>) {
-        if a < 1 || b < 1 {
-            return;
+    pub fn blit_region(
+        &mut self,
+        src: &Canvas,
+        src_rect: (i32, i32, i32, i32),
+        dst_x: i32,
+        dst_y: i32,
+    ) {
+        if let Some(blit) = self.clip_blit(src, src_rect, dst_x, dst_y) {
+            for row in 0..blit.height {
+                let (src_start, dst_start) = blit.row_starts(row, src.width, self.width);
+                self.buffer[dst_start..dst_start + blit.width]
+                    .copy_from_slice(&src.buffer[src_start..src_start + blit.width]);
+            }
         }
+    }
 
-        let raw_color = u32::from(color.into());
-
-        let mut i = -a;
-        let mut j = 0;
-
-        // change to larger integers to avoid overflow.
-        let b2 = i64::from(b) * i64::from(b);
-        let a2 = i64::from(a) * i64::from(a);
-        let mut err = i64::from(i) * (2 * b2 + i64::from(i)) + b2;
-
-        loop {
-            let x1 = x - i;
-            let x2 = x + i;
-            let y1 = y - j;
-            let y2 = y + j;
-
-            // TODO: benchmark this with precise tooling against just using self.set_pixel()
-            // flamegraph shows a siginificant difference, but I'm not convinced.
-            if 0 <= x1 && x1 < self.clamped_width {
-                if 0 <= y1 && y1 < self.clamped_height {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x1, y1, raw_color);
-                    }
-                }
-                if 0 <= y2 && y2 < self.clamped_height {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x1, y2, raw_color);
-                    }
-                }
-            }
-            if 0 <= x2 && x2 < self.clamped_width {
-                if 0 <= y1 && y1 < self.clamped_height {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x2, y1, raw_color);
-                    }
-                }
-                if 0 <= y2 && y2 < self.clamped_height {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x2, y2, raw_color);
-                    }
+    /// Blends the whole of `src` onto this [`Canvas`] with its top left corner at (dst_x, dst_y), using `mask` as per-pixel coverage:
+    /// 0 keeps the destination pixel, 255 replaces it with the source pixel, and values in between mix the two (see [`Color::lerp`]).
+    /// Useful for soft-edged stamps and anti-aliased glyphs. The destination is clipped to this canvas.
+    /// # Panics
+    /// This function panics if the length of `mask` does not match the size of `src`.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut stamp = [Color::WHITE.0; 3];
+    /// let stamp = Canvas::new(&mut stamp, 3, 1);
+    ///
+    /// let mut buffer = [Color::BLACK.0; 4];
+    /// let mut canvas = Canvas::new(&mut buffer, 4, 1);
+    /// canvas.blit_masked(&stamp, &[255, 128, 0], 1, 0);
+    ///
+    /// assert_eq!(buffer, [Color::BLACK.0, Color::WHITE.0, Color::gray(128).0, Color::BLACK.0]);
+    /// ```
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn blit_masked(&mut self, src: &Canvas, mask: &[u8], dst_x: i32, dst_y: i32) {
+        assert!(
+            mask.len() == src.buffer.len(),
+            "the mask size does not match the source size"
+        );
+
+        let src_rect = (0, 0, src.clamped_width, src.clamped_height);
+        if let Some(blit) = self.clip_blit(src, src_rect, dst_x, dst_y) {
+            for row in 0..blit.height {
+                let (src_start, dst_start) = blit.row_starts(row, src.width, self.width);
+                let src_range = src_start..src_start + blit.width;
+
+                for ((dst, src), coverage) in self.buffer[dst_start..dst_start + blit.width]
+                    .iter_mut()
+                    .zip(&src.buffer[src_range.clone()])
+                    .zip(&mask[src_range])
+                {
+                    *dst = match coverage {
+                        0 => *dst,
+                        255 => *src,
+                        _ => {
+                            Color(*dst)
+                                .lerp(Color(*src), f32::from(*coverage) / 255.0)
+                                .0
+                        }
+                    };
                 }
             }
-
-            let e2 = 2 * err;
-            if e2 >= i64::from(i * 2 + 1) * b2 {
-                i += 1;
-                err += i64::from(i * 2 + 1) * b2;
-            }
-
-            if e2 <= i64::from(j * 2 + 1) * a2 {
-                j += 1;
-                err += i64::from(j * 2 + 1) * a2;
-            }
-
-            if i > 0 {
-                break;
-            }
         }
+    }
 
-        while j < b {
-            j += 1;
-            if 0 <= x && x < self.clamped_width {
-                let y1 = y + j;
-                let y2 = y - j;
-                if 0 <= y1 && y1 < self.clamped_height {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x, y1, raw_color);
-                    }
-                }
-                if 0 <= y2 && y2 < self.clamped_height {
-                    unsafe {
-                        self.set_pixel_unchecked_raw_i32(x, y2, raw_color);
-                    }
-                }
-            }
+    // Clips the source rectangle (x, y, w, h) to `src`, and its copy at (dst_x, dst_y) to this canvas.
+    // Returns None if nothing is left.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn clip_blit(
+        &self,
+        src: &Canvas,
+        (src_x, src_y, src_w, src_h): (i32, i32, i32, i32),
+        dst_x: i32,
+        dst_y: i32,
+    ) -> Option<Blit> {
+        // work in i64 so that no sum can overflow
+        let (src_x, src_y) = (i64::from(src_x), i64::from(src_y));
+        let (dst_x, dst_y) = (i64::from(dst_x), i64::from(dst_y));
+
+        // clip to the source, then to the destination (both in source coordinates)
+        let from_x = src_x.max(0).max(src_x - dst_x);
+        let from_y = src_y.max(0).max(src_y - dst_y);
+        let to_x = (src_x + i64::from(src_w))
+            .min(i64::from(src.clamped_width))
+            .min(src_x - dst_x + i64::from(self.clamped_width));
+        let to_y = (src_y + i64::from(src_h))
+            .min(i64::from(src.clamped_height))
+            .min(src_y - dst_y + i64::from(self.clamped_height));
+
+        if from_x >= to_x || from_y >= to_y {
+            return None;
         }
+
+        Some(Blit {
+            src: (from_x as usize, from_y as usize),
+            dst: (
+                (from_x - src_x + dst_x) as usize,
+                (from_y - src_y + dst_y) as usize,
+            ),
+            width: (to_x - from_x) as usize,
+            height: (to_y - from_y) as usize,
+        })
     }
 
-    /// Renders a triangle in this [`Canvas`].
-    /// ``` rust
-    /// use vason::{Canvas, Color};
+    /// Compares this [`Canvas`] with another one pixel by pixel.
+    /// Returns `None` if they are identical, otherwise the number of differing pixels and their bounding box.
+    /// If the dimensions of the canvases differ, [`DiffStats::DimensionMismatch`] is returned.
+    /// Useful for comparing rendered images against reference images in tests.
+    /// ```rust
+    /// use vason::{canvas::DiffStats, Canvas, Color};
     /// let mut buffer = [0u32; 256];
     /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.fill_triangle(1, 0, 12, 0, 13, 15, Color::RED);
+    /// canvas.fill_rect(4, 4, 2, 3, Color::RED);
+    ///
+    /// let mut reference = [0u32; 256];
+    /// let reference = Canvas::new(&mut reference, 16, 16);
+    ///
+    /// assert_eq!(canvas.diff(&canvas), None);
+    /// assert_eq!(
+    ///     canvas.diff(&reference),
+    ///     Some(DiffStats::Pixels { count: 6, bounds: (4, 4, 2, 3) })
+    /// );
     /// ```
-    #[allow(
-        clippy::too_many_arguments,
-        clippy::cast_sign_loss,
-        clippy::cast_possible_truncation
-    )]
-    pub fn fill_triangle(
-        &mut self,
-        mut x1: i32,
-        mut y1: i32,
-        mut x2: i32,
-        mut y2: i32,
-        mut x3: i32,
-        mut y3: i32,
-        color: impl Into<Color>,
-    ) {
-        use std::mem::swap;
-        let raw_color = u32::from(color.into());
+    #[must_use]
+    pub fn diff(&self, other: &Canvas) -> Option<DiffStats> {
+        self.diff_with_tolerance(other, 0)
+    }
 
-        // Sort points vertically
-        if y2 > y3 {
-            swap(&mut x2, &mut x3);
-            swap(&mut y2, &mut y3);
+    /// Compares this [`Canvas`] with another one pixel by pixel. Two pixels are considered equal
+    /// if none of their channels (including alpha) differ by more than `tolerance`.
+    /// See [`diff`](struct.Canvas.html#method.diff).
+    /// ```rust
+    /// use vason::{canvas::DiffStats, Canvas, Color};
+    /// let mut buffer = [0u32; 4];
+    /// let mut canvas = Canvas::new(&mut buffer, 2, 2);
+    /// canvas.clear(Color::rgb(100, 100, 100));
+    ///
+    /// let mut reference = [0u32; 4];
+    /// let mut reference = Canvas::new(&mut reference, 2, 2);
+    /// reference.clear(Color::rgb(102, 99, 100));
+    ///
+    /// assert_eq!(canvas.diff_with_tolerance(&reference, 2), None);
+    /// assert!(canvas.diff_with_tolerance(&reference, 1).is_some());
+    /// ```
+    #[must_use]
+    pub fn diff_with_tolerance(&self, other: &Canvas, tolerance: u8) -> Option<DiffStats> {
+        if self.width != other.width || self.height != other.height {
+            return Some(DiffStats::DimensionMismatch);
         }
 
-        if y1 > y2 {
-            swap(&mut x1, &mut x2);
-            swap(&mut y1, &mut y2);
-        }
+        let mut count = 0;
+        let (mut min_x, mut max_x) = (usize::MAX, 0);
+        let (mut min_y, mut max_y) = (usize::MAX, 0);
 
-        if y2 > y3 {
-            swap(&mut x2, &mut x3);
-            swap(&mut y2, &mut y3);
-        }
+        for (i, (&a, &b)) in self.buffer.iter().zip(other.buffer.iter()).enumerate() {
+            let differs = !within_tolerance(a, b, tolerance);
 
-        let dx_far = f64::from(x3 - x1) / f64::from(y3 - y1 + 1);
-        let dx_upper = f64::from(x2 - x1) / f64::from(y2 - y1 + 1);
-        let dx_low = f64::from(x3 - x2) / f64::from(y3 - y2 + 1);
-        let mut xf = f64::from(x1);
-        let mut xt = xf + dx_upper;
+            if differs {
+                let (x, y) = (i % self.width, i / self.width);
+                count += 1;
+                min_x = min_x.min(x);
+                max_x = max_x.max(x);
+                min_y = min_y.min(y);
+                max_y = max_y.max(y);
+            }
+        }
 
-        for y in y1..=y3.min(self.clamped_height - 1) {
-            if y >= 0 {
-                let offset = y as usize * self.width;
-                {
-                    let from_x = xf.max(0.0) as usize;
-                    let to_x = if xt < f64::from(self.clamped_width) {
-                        xt as usize
-                    } else {
-                        (self.clamped_width - 1) as usize
-                    };
+        (count > 0).then(|| DiffStats::Pixels {
+            count,
+            bounds: (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1),
+        })
+    }
 
-                    let range = offset + from_x..=offset + to_x;
+    /// Returns a copy of the pixels of this [`Canvas`] in which every pixel that differs from `other` is replaced with `highlight`.
+    /// Saving the result (e.g. with [`ppm::encode_buffer`](crate::ppm::encode_buffer)) shows at a glance where a rendering changed.
+    /// # Errors
+    /// Like [`diff`](struct.Canvas.html#method.diff), this returns [`DiffStats::DimensionMismatch`] if the dimensions of the canvases differ.
+    /// ```rust
+    /// use vason::{canvas::DiffStats, Canvas, Color};
+    /// let mut buffer = [0u32; 4];
+    /// let mut canvas = Canvas::new(&mut buffer, 2, 2);
+    /// canvas.set_pixel(1, 0, Color::BLUE);
+    ///
+    /// let mut reference = [0u32; 4];
+    /// let reference = Canvas::new(&mut reference, 2, 2);
+    /// assert_eq!(canvas.diff_image(&reference, Color::RED), Ok(vec![0, Color::RED.0, 0, 0]));
+    ///
+    /// let mut other = [0u32; 4];
+    /// let other = Canvas::new(&mut other, 4, 1);
+    /// assert_eq!(canvas.diff_image(&other, Color::RED), Err(DiffStats::DimensionMismatch));
+    /// ```
+    pub fn diff_image(
+        &self,
+        other: &Canvas,
+        highlight: impl Into<Color>,
+    ) -> Result<Vec<u32>, DiffStats> {
+        if self.width != other.width || self.height != other.height {
+            return Err(DiffStats::DimensionMismatch);
+        }
 
-                    if !range.is_empty() {
-                        self.buffer[range].fill(raw_color);
-                    }
-                }
+        let highlight = u32::from(highlight.into());
+        Ok(self
+            .buffer
+            .iter()
+            .zip(other.buffer.iter())
+            .map(|(&a, &b)| if a == b { a } else { highlight })
+            .collect())
+    }
 
-                {
-                    let from_x = xt.max(0.0) as usize;
-                    let to_x = if xf < f64::from(self.clamped_width) {
-                        xf as usize
-                    } else {
-                        self.clamped_width as usize - 1
-                    };
+    /// Returns a 64-bit hash (FNV-1a) of the dimensions and the pixels of this [`Canvas`].
+    /// The hash does not depend on the platform, so it can be used to pin a rendered image in tests
+    /// without having to store the whole reference image.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// let empty = canvas.content_hash();
+    ///
+    /// canvas.set_pixel(3, 3, Color::RED);
+    /// assert_ne!(canvas.content_hash(), empty);
+    ///
+    /// canvas.set_pixel(3, 3, 0u32);
+    /// assert_eq!(canvas.content_hash(), empty);
+    /// ```
+    #[must_use]
+    pub fn content_hash(&self) -> u64 {
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let width = (self.width as u64).to_le_bytes();
+        let pixels = self.buffer.iter().flat_map(|p| p.to_le_bytes());
+
+        width
+            .into_iter()
+            .chain(pixels)
+            .fold(OFFSET_BASIS, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+            })
+    }
 
-                    let range = offset + from_x..=offset + to_x;
-                    if !range.is_empty() {
-                        self.buffer[range].fill(raw_color);
-                    }
-                }
-            }
+    /// Writes the pixels of this [`Canvas`] as little-endian bytes, row by row, without any header.
+    /// This is the fastest way to cache a rendered image between runs; use [`load_raw`](struct.Canvas.html#method.load_raw) to read it back.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there was an i/o error whilest writing.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 6];
+    /// let mut canvas = Canvas::new(&mut buffer, 3, 2);
+    /// canvas.set_pixel(2, 1, Color::RED);
+    ///
+    /// let mut bytes = Vec::new();
+    /// canvas.save_raw(&mut bytes).unwrap();
+    ///
+    /// let mut loaded = Canvas::load_raw(&mut bytes.as_slice(), 3, 2).unwrap();
+    /// assert_eq!(Canvas::new(&mut loaded, 3, 2).diff(&canvas), None);
+    /// ```
+    pub fn save_raw(&self, w: &mut dyn Write) -> io::Result<()> {
+        let bytes: Vec<u8> = self.buffer.iter().flat_map(|p| p.to_le_bytes()).collect();
+        w.write_all(&bytes)
+    }
 
-            xf += dx_far;
-            if y < y2 {
-                xt += dx_upper;
-            } else {
-                xt += dx_low;
-            }
+    /// Reads a buffer of `width * height` pixels written by [`save_raw`](struct.Canvas.html#method.save_raw).
+    /// The result can be turned into a canvas with [`Canvas::new`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there was an i/o error whilest reading,
+    /// or an [`InvalidData`](io::ErrorKind::InvalidData) error if the number of bytes read is not `width * height * 4`.
+    /// ```rust
+    /// use vason::Canvas;
+    /// let bytes = [0u8; 4 * 5];
+    ///
+    /// assert_eq!(Canvas::load_raw(&mut &bytes[..], 5, 1).unwrap().len(), 5);
+    /// assert!(Canvas::load_raw(&mut &bytes[..], 2, 2).is_err());
+    /// ```
+    pub fn load_raw(r: &mut dyn Read, width: usize, height: usize) -> io::Result<Vec<u32>> {
+        let mut bytes = Vec::new();
+        r.read_to_end(&mut bytes)?;
+
+        let expected = width.checked_mul(height).and_then(|len| len.checked_mul(4));
+        if expected != Some(bytes.len()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "raw data does not match the canvas size",
+            ));
         }
+
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|p| u32::from_le_bytes([p[0], p[1], p[2], p[3]]))
+            .collect())
     }
 
-    /// Renders the outline of a triangle in this [`Canvas`].
+    /// Encodes this [`Canvas`] with `encoder` and writes it to a file at `path`, replacing the file if it exists.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file could not be created or there was an i/o error whilest writing.
+    /// ```rust
+    /// use vason::{ppm, Canvas};
+    /// let mut buffer = [0u32; 16];
+    /// let canvas = Canvas::new(&mut buffer, 4, 4);
+    ///
+    /// let path = std::env::temp_dir().join("vason_save_example.pbm");
+    /// canvas.save(&path, &ppm::Pbm { threshold: 128 }).unwrap();
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save(&self, path: impl AsRef<Path>, encoder: &dyn ImageEncoder) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        encoder.encode(self, &mut file)?;
+        file.flush()
+    }
+
+    /// Fills a rectangle shaped region in this [`Canvas`]. If width or height is <= 0 nothing is drawn.
+    /// The coordinates and sizes can be of any [`Coord`] type.
     /// ``` rust
     /// use vason::{Canvas, Color};
     /// let mut buffer = [0u32; 256];
     /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.outline_triangle(1, 0, 12, 0, 13, 15, Color::RED);
+    /// canvas.fill_rect(3, 3, 7, 7, Color::RED);
+    /// canvas.fill_rect(1usize, 2usize, 3usize, 4usize, Color::BLUE);
     /// ```
-    #[allow(clippy::too_many_arguments)]
-    pub fn outline_triangle(
+    #[allow(clippy::cast_sign_loss)]
+    pub fn fill_rect(
         &mut self,
-        x1: i32,
-        y1: i32,
-        x2: i32,
-        y2: i32,
-        x3: i32,
-        y3: i32,
+        x: impl Coord,
+        y: impl Coord,
+        w: impl Coord,
+        h: impl Coord,
         color: impl Into<Color>,
     ) {
+        let (x, y, w, h) = (x.to_i32(), y.to_i32(), w.to_i32(), h.to_i32());
         let raw_color = u32::from(color.into());
+        let (from_x, to_x, from_y, to_y) =
+            self.clamp_rect_i32(x, x.saturating_add(w), y, y.saturating_add(h));
+
+        let offset = from_y as usize * self.width;
+        let mut from_idx = offset + from_x as usize;
+        let mut to_idx = offset + to_x as usize;
+
+        for _ in from_y..to_y {
+            self.fill_span_raw(from_idx..to_idx, raw_color);
+            from_idx += self.width;
+            to_idx += self.width;
+        }
+    }
 
-        self.line(x1, y1, x2, y2, raw_color);
-        self.line(x1, y1, x3, y3, raw_color);
-        self.line(x2, y2, x3, y3, raw_color);
+    /// Fills a rectangle shaped region in this [`Canvas`] given in floating point coordinates.
+    /// The edges (x, x + w, y and y + h) are rounded to the nearest integer, halfway cases away from zero (like [`f32::round`]),
+    /// so adjacent rectangles never overlap or leave gaps. If the rounded width or height is <= 0 nothing is drawn.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.fill_rect_f32(0.0, 0.0, 2.5, 1.0, Color::RED);
+    /// canvas.fill_rect_f32(2.5, 0.0, 2.5, 1.0, Color::BLUE);
+    ///
+    /// let (r, b) = (Color::RED.0, Color::BLUE.0);
+    /// assert_eq!(&buffer[..6], &[r, r, r, b, b, 0]);
+    /// ```
+    pub fn fill_rect_f32(&mut self, x: f32, y: f32, w: f32, h: f32, color: impl Into<Color>) {
+        let (x1, y1) = (x.to_i32(), y.to_i32());
+        let (x2, y2) = ((x + w).to_i32(), (y + h).to_i32());
+        self.fill_rect(x1, y1, x2.saturating_sub(x1), y2.saturating_sub(y1), color);
     }
 
-    /// Renders the outline of a triangle with thickness in this [`Canvas`]. Joints are covered by rounded ends (circles).
+    /// Renders the outline of a rectangle shaped region in this [`Canvas`]. If width or height is <= 0 nothing is drawn.
     /// ``` rust
     /// use vason::{Canvas, Color};
     /// let mut buffer = [0u32; 256];
     /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.thick_outline_triangle(1, 0, 12, 0, 13, 15, 3, Color::RED);
+    /// canvas.outline_rect(3, 3, 7, 7, Color::RED);
     /// ```
-    #[allow(clippy::too_many_arguments)]
-    pub fn thick_outline_triangle(
+    pub fn outline_rect(&mut self, x: i32, y: i32, w: i32, h: i32, color: impl Into<Color>) {
+        // consistency with fill_rect
+        if w <= 0 || h <= 0 {
+            return;
+        }
+
+        let raw_color = u32::from(color.into());
+
+        let x2 = x.saturating_add(w - 1);
+        let y2 = y.saturating_add(h - 1);
+
+        // the top and bottom rows span the whole width, the columns only what is between them,
+        // so no pixel is drawn twice
+        self.fill_rect(x, y, w, 1, raw_color);
+        if h > 1 {
+            self.fill_rect(x, y2, w, 1, raw_color);
+        }
+        if h > 2 {
+            self.fill_rect(x, y.saturating_add(1), 1, h - 2, raw_color);
+            if w > 1 {
+                self.fill_rect(x2, y.saturating_add(1), 1, h - 2, raw_color);
+            }
+        }
+    }
+
+    /// Renders the outline of a rectangle shaped region with a given thickness in this [`Canvas`]. If the width, height or thickness is <= 0 nothing is drawn.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16,16);
+    /// canvas.thick_outline_rect(3, 3, 7, 7, 2, Color::RED);
+    /// ```
+    pub fn thick_outline_rect(
         &mut self,
-        x1: i32,
-        y1: i32,
-        x2: i32,
-        y2: i32,
-        x3: i32,
-        y3: i32,
+        x: i32,
+        y: i32,
+        w: i32,
+        h: i32,
         thickness: i32,
         color: impl Into<Color>,
     ) {
-        if thickness < 0 {
+        // consistency with fill_rect
+        if w <= 0 || h <= 0 || thickness <= 0 {
             return;
         } else if thickness == 1 {
-            self.outline_triangle(x1, y1, x2, y2, x3, y3, color);
+            self.outline_rect(x, y, w, h, color);
             return;
         }
 
@@ -789,378 +1459,3776 @@ impl<'a> Canvas<'a> {
 
         let half_thickness = thickness / 2;
 
-        self.thick_line(x1, y1, x2, y2, thickness, raw_color);
-        self.thick_line(x1, y1, x3, y3, thickness, raw_color);
-        self.thick_line(x2, y2, x3, y3, thickness, raw_color);
-        self.fill_circle(x1, y1, half_thickness, raw_color);
-        self.fill_circle(x2, y2, half_thickness, raw_color);
-        self.fill_circle(x3, y3, half_thickness, raw_color);
+        // the outer and inner edges of the stroke
+        let left = x.saturating_sub(half_thickness);
+        let top = y.saturating_sub(half_thickness);
+        let right = x.saturating_add(w).saturating_add(half_thickness);
+        let bottom = y.saturating_add(h).saturating_add(half_thickness);
+        let inner_left = x.saturating_add(half_thickness);
+        let inner_top = y.saturating_add(half_thickness);
+        let inner_right = x
+            .saturating_add(w)
+            .saturating_sub(half_thickness)
+            .max(inner_left);
+        let inner_bottom = y
+            .saturating_add(h)
+            .saturating_sub(half_thickness)
+            .max(inner_top);
+
+        // the horizontal bands span the whole width, the vertical ones only what is between them,
+        // so no pixel is drawn twice
+        self.fill_rect_between(left, top, right, inner_top, raw_color);
+        self.fill_rect_between(left, inner_bottom, right, bottom, raw_color);
+        self.fill_rect_between(left, inner_top, inner_left, inner_bottom, raw_color);
+        self.fill_rect_between(inner_right, inner_top, right, inner_bottom, raw_color);
     }
 
-    /// Renders a horizontal line. Should be preferred when explicitly drawing horizontal lines.
+    /// Fills a circle shaped region in this [`Canvas`]. The radius must be positive.
     /// ``` rust
     /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32;256];
+    /// let mut buffer = [0u32; 256];
     /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.hline(10, 0, 16, Color::RED);
+    /// canvas.fill_circle(8, 8, 4, Color::GREEN);
     /// ```
-    #[allow(clippy::cast_sign_loss)]
-    #[inline]
-    pub fn hline(&mut self, y: i32, x1: i32, x2: i32, color: impl Into<Color>) {
+    pub fn fill_circle(&mut self, x: i32, y: i32, r: i32, color: impl Into<Color>) {
         let raw_color = u32::from(color.into());
+        self.fill_circle_spans(x, y, r, |canvas, range| {
+            canvas.fill_span_raw(range, raw_color);
+        });
+    }
 
-        if 0 <= y && y < self.clamped_height {
-            let (x1, x2) = if x1 > x2 { (x2, x1) } else { (x1, x2) };
-            let from_x = x1.clamp(0, self.clamped_width - 1);
-            let to_x = (x2 + 1).clamp(from_x, self.clamped_width);
-            let offset = y as usize * self.width;
-            let range = offset + from_x as usize..offset + to_x as usize;
-            self.buffer[range].fill(raw_color);
+    /// Fills a circle shaped region in this [`Canvas`] by adding the color to the pixels already there (saturating, see [`Color`]'s `Add`),
+    /// instead of overwriting them. Stacking several dim additive circles makes for soft glows and light accumulation. The radius must be positive.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.clear(Color::BLACK);
+    ///
+    /// // a glow that gets brighter towards its center
+    /// for r in (2..=6).step_by(2) {
+    ///     canvas.fill_circle_add(8, 8, r, Color::rgb(40, 30, 10));
+    /// }
+    ///
+    /// assert_eq!(buffer[8 * 16 + 8], Color::rgb(120, 90, 30).0);
+    /// assert_eq!(buffer[8 * 16 + 3], Color::rgb(40, 30, 10).0);
+    /// ```
+    pub fn fill_circle_add(&mut self, x: i32, y: i32, r: i32, color: impl Into<Color>) {
+        let color = color.into();
+        self.fill_circle_spans(x, y, r, |canvas, range| {
+            for pixel in &mut canvas.buffer[range] {
+                *pixel = (Color(*pixel) + color).0;
+            }
+        });
+    }
+
+    /// Fills an anti-aliased circle shaped region in this [`Canvas`]. The radius must be positive.
+    /// Pixels on the edge are alpha blended with the color weighted by how much of them the circle covers,
+    /// the ones fully inside are filled just like with [`fill_circle`](struct.Canvas.html#method.fill_circle).
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.clear(Color::BLACK);
+    /// canvas.fill_circle_aa(8, 8, 5, Color::WHITE);
+    ///
+    /// assert_eq!(buffer[8 * 16 + 8], Color::WHITE.0);
+    /// // a pixel on the edge is a shade of gray
+    /// let edge = Color(buffer[4 * 16 + 5]).to_rgb().0;
+    /// assert!(0 < edge && edge < 255);
+    /// ```
+    pub fn fill_circle_aa(&mut self, x: i32, y: i32, r: i32, color: impl Into<Color>) {
+        if r < 1 {
+            return;
         }
+
+        let r = f64::from(r);
+        self.circle_aa(x, y, r - 0.5, r + 0.5, true, color.into(), |d| r + 0.5 - d);
     }
 
-    /// Renders a vertical line. Should be preferred when explicitly drawing vertical lines.
+    /// Renders an anti-aliased outline of a circle shaped region in this [`Canvas`]. The radius must be positive.
+    /// The outline is one pixel wide, pixels near it are alpha blended with the color weighted by their distance from the circle.
     /// ``` rust
     /// use vason::{Canvas, Color};
     /// let mut buffer = [0u32; 256];
     /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.vline(10, 0, 16, Color::RED);
+    /// canvas.outline_circle_aa(8, 8, 6, Color::RED);
+    ///
+    /// // exactly on the circle
+    /// assert_eq!(buffer[8 * 16 + 14], Color::RED.0);
+    /// assert_eq!(buffer[8 * 16 + 8], 0);
     /// ```
-    #[allow(clippy::cast_sign_loss)]
-    #[inline]
-    pub fn vline(&mut self, x: i32, y1: i32, y2: i32, color: impl Into<Color>) {
+    pub fn outline_circle_aa(&mut self, x: i32, y: i32, r: i32, color: impl Into<Color>) {
+        if r < 1 {
+            return;
+        }
+
+        let r = f64::from(r);
+        self.circle_aa(x, y, r - 1.0, r + 1.0, false, color.into(), |d| {
+            1.0 - (d - r).abs()
+        });
+    }
+
+    /// Renders the outline of a circle shaped region in this [`Canvas`]. The radius must be positive,
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16,16);
+    /// canvas.outline_circle(8, 8, 8, Color::YELLOW);
+    /// ```
+    #[allow(clippy::cast_sign_loss, clippy::many_single_char_names)]
+    pub fn outline_circle(&mut self, x: i32, y: i32, mut r: i32, color: impl Into<Color>) {
+        if r < 1 {
+            return;
+        }
+
         let raw_color = u32::from(color.into());
+        let mut i = -r;
+        let mut j = 0;
+        let mut err = 2 - 2 * r;
+        loop {
+            let x1 = x - i;
+            let x2 = x + i;
+            let y1 = y - j;
+            let y2 = y + j;
 
-        if 0 <= x && x < self.clamped_width {
-            let (y1, y2) = if y1 > y2 { (y2, y1) } else { (y1, y2) };
+            // TODO: benchmark this with precise tooling against just using self.set_pixel()
+            // flamegraph shows a siginificant difference, but I'm not convinced.
+            if 0 <= x1 && x1 < self.clamped_width {
+                if 0 <= y1 && y1 < self.clamped_height {
+                    unsafe {
+                        self.set_pixel_unchecked_raw_i32(x1, y1, raw_color);
+                    }
+                }
+                // the leftmost and rightmost points of the circle are on a single row
+                if j != 0 && 0 <= y2 && y2 < self.clamped_height {
+                    unsafe {
+                        self.set_pixel_unchecked_raw_i32(x1, y2, raw_color);
+                    }
+                }
+            }
+            if 0 <= x2 && x2 < self.clamped_width {
+                if 0 <= y1 && y1 < self.clamped_height {
+                    unsafe {
+                        self.set_pixel_unchecked_raw_i32(x2, y1, raw_color);
+                    }
+                }
+                if j != 0 && 0 <= y2 && y2 < self.clamped_height {
+                    unsafe {
+                        self.set_pixel_unchecked_raw_i32(x2, y2, raw_color);
+                    }
+                }
+            }
 
-            let from_y = y1.clamp(0, self.clamped_height - 1);
-            let to_y = (y2 + 1).clamp(from_y, self.clamped_height);
+            r = err;
+            if r <= j {
+                j += 1;
+                err += j * 2 + 1;
+            }
+            if r > i || err > j {
+                i += 1;
+                err += i * 2 + 1;
+            }
 
-            for y in from_y..to_y {
-                unsafe { self.set_pixel_unchecked_raw_i32(x, y, raw_color) }
+            if i >= 0 {
+                break;
             }
         }
     }
 
-    /// Renders a horizontal line with thickness. Should be preferred when explicitly drawing thick horizontal lines.
+    /// Renders the outline of a circle shaped region with a given thickness in this [`Canvas`]. The radius must be positive.
+    /// The stroke witdth grows symmetrically (inwards and outwards), that is the supplied radius will be the center of the stroke.
     /// ``` rust
     /// use vason::{Canvas, Color};
     /// let mut buffer = [0u32; 256];
     /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.thick_hline(10, 0, 16, 2, Color::RED);
+    /// canvas.thick_outline_circle(4, 8, 8, 2, Color::CYAN);
     /// ```
-    #[inline]
-    pub fn thick_hline(
+    #[allow(clippy::similar_names)]
+    pub fn thick_outline_circle(
         &mut self,
+        x: i32,
         y: i32,
-        x1: i32,
-        x2: i32,
+        r: i32,
         thickness: i32,
         color: impl Into<Color>,
     ) {
-        let thickness = thickness.max(0);
-        let (x1, x2) = if x1 > x2 { (x2, x1) } else { (x1, x2) };
-        self.fill_rect(x1, y + thickness / 2, x2 - x1, thickness, color);
+        if thickness <= 0 || r < 1 {
+            return;
+        } else if thickness == 1 {
+            self.outline_circle(x, y, r, color);
+            return;
+        }
+
+        let raw_color = u32::from(color.into());
+
+        let half_thickness = thickness / 2;
+
+        let ro = r + half_thickness;
+        let ri = ro - thickness + 1;
+
+        let mut xo = ro;
+        let mut xi = ri;
+        let mut j = 0;
+        let mut erro = 1 - xo;
+        let mut erri = 1 - xi;
+
+        // The octants overlap at their seams, so the spans are collected and their union is filled,
+        // drawing every pixel once.
+        let mut spans = Vec::new();
+        let height = self.clamped_height;
+        let mut hline = |row: i32, x1: i32, x2: i32| {
+            if 0 <= row && row < height {
+                spans.push((row, x1.min(x2), x1.max(x2)));
+            }
+        };
+
+        while xo >= j {
+            for &(row, x1, x2) in &[
+                (y + j, x + xi, x + xo),
+                (y + j, x - xo, x - xi),
+                (y - j, x - xo, x - xi),
+                (y - j, x + xi, x + xo),
+            ] {
+                hline(row, x1, x2);
+            }
+            for &(column, y1, y2) in &[
+                (x + j, y + xi, y + xo),
+                (x - j, y + xi, y + xo),
+                (x - j, y - xo, y - xi),
+                (x + j, y - xo, y - xi),
+            ] {
+                for row in y1.min(y2)..=y1.max(y2) {
+                    hline(row, column, column);
+                }
+            }
+
+            j += 1;
+
+            if erro < 0 {
+                erro += 2 * j + 1;
+            } else {
+                xo -= 1;
+                erro += 2 * (j - xo) + 1;
+            }
+
+            if j > ri {
+                xi = j;
+            } else if erri < 0 {
+                erri += 2 * j + 1;
+            } else {
+                xi -= 1;
+                erri += 2 * (j - xi) + 1;
+            }
+        }
+
+        self.fill_span_union(spans, raw_color);
+    }
+
+    /// Renders the part of a circle outline between two angles (in radians) in this [`Canvas`]. The radius must be positive.
+    /// Angles are measured from the positive x axis and grow clockwise (as the y axis points down).
+    /// The arc sweeps from `start` towards increasing angles until it reaches `end`, so sweeps crossing 0 (e.g. from 5.5 to 0.5) work as expected.
+    /// If the angles are a full turn or more apart, the whole circle is drawn.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// use std::f32::consts::PI;
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    ///
+    /// // the right half of a circle, crossing 0
+    /// canvas.outline_arc(8, 8, 6, 1.5 * PI, 0.5 * PI, Color::RED);
+    ///
+    /// assert_eq!(buffer[8 * 16 + 14], Color::RED.0);
+    /// assert_eq!(buffer[8 * 16 + 2], 0);
+    /// ```
+    pub fn outline_arc(
+        &mut self,
+        x: i32,
+        y: i32,
+        r: i32,
+        start: f32,
+        end: f32,
+        color: impl Into<Color>,
+    ) {
+        if r < 1 {
+            return;
+        }
+
+        let raw_color = u32::from(color.into());
+        let sweep = Sweep::new(start, end);
+
+        self.outline_circle_offsets(r, |canvas, dx, dy| {
+            if sweep.contains(f64::from(dx), f64::from(dy)) {
+                canvas.try_set_pixel(x.saturating_add(dx), y.saturating_add(dy), raw_color);
+            }
+        });
+    }
+
+    /// Fills a pie slice (a wedge of a circle) between two angles (in radians) in this [`Canvas`]. The radius must be positive.
+    /// The two radii bounding the slice and the center are always filled. Angles are interpreted as in [`outline_arc`](struct.Canvas.html#method.outline_arc).
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// use std::f32::consts::PI;
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    ///
+    /// // a pie chart with a quarter missing
+    /// canvas.fill_pie(8, 8, 7, 0.0, 1.5 * PI, Color::RED);
+    ///
+    /// assert_eq!(buffer[10 * 16 + 6], Color::RED.0);
+    /// assert_eq!(buffer[6 * 16 + 11], 0);
+    /// ```
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fill_pie(
+        &mut self,
+        x: i32,
+        y: i32,
+        r: i32,
+        start: f32,
+        end: f32,
+        color: impl Into<Color>,
+    ) {
+        let raw_color = u32::from(color.into());
+        let sweep = Sweep::new(start, end);
+        let (raster_op, blend_mode) = (self.raster_op, self.blend_mode);
+
+        self.fill_circle_spans(x, y, r, |canvas, range| {
+            for index in range {
+                let dx = (index % canvas.width) as f64 - f64::from(x);
+                let dy = (index / canvas.width) as f64 - f64::from(y);
+                if sweep.contains(dx, dy) || sweep.on_radius(dx, dy) {
+                    let pixel = &mut canvas.buffer[index];
+                    *pixel = combine(raster_op, blend_mode, *pixel, raw_color);
+                }
+            }
+        });
+    }
+
+    /// Fills an ellipse shaped region in this [`Canvas`]. The radii must be positive.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.fill_ellipse(8, 8, 8, 4, Color::RED);
+    /// ```
+    #[allow(clippy::many_single_char_names, clippy::cast_sign_loss)]
+    pub fn fill_ellipse(&mut self, x: i32, y: i32, a: i32, b: i32, color: impl Into<Color>) {
+        if a < 1 || b < 1 {
+            return;
+        }
+
+        let raw_color = u32::from(color.into());
+
+        let mut i = -a;
+        let mut j = 0;
+
+        // change to larger integers to avoid overflow.
+        let b2 = i64::from(b) * i64::from(b);
+        let a2 = i64::from(a) * i64::from(a);
+        let mut err = i64::from(i) * (2 * b2 + i64::from(i)) + b2;
+
+        // the first span of a row is always the widest one, each row is filled only once
+        let mut last_j = -1;
+        loop {
+            if j != last_j {
+                last_j = j;
+                //i is non-positive
+                let from_x = x.saturating_add(i).clamp(0, self.clamped_width);
+                let to_x = x.saturating_sub(i).clamp(from_x, self.clamped_width);
+
+                let rows = [y.saturating_sub(j), y.saturating_add(j)];
+                for &row in &rows[..if j == 0 { 1 } else { 2 }] {
+                    if 0 <= row && row < self.clamped_height {
+                        let offset = row as usize * self.width;
+                        let range = offset + from_x as usize..offset + to_x as usize;
+                        self.fill_span_raw(range, raw_color);
+                    }
+                }
+            }
+
+            let e2 = 2 * err;
+            if e2 >= i64::from(i * 2 + 1) * b2 {
+                i += 1;
+                err += i64::from(i * 2 + 1) * b2;
+            }
+
+            if e2 <= i64::from(j * 2 + 1) * a2 {
+                j += 1;
+                err += i64::from(j * 2 + 1) * a2;
+            }
+
+            if i > 0 {
+                break;
+            }
+        }
+
+        while j < b {
+            j += 1;
+            if 0 <= x && x < self.clamped_width {
+                let y1 = y + j;
+                let y2 = y - j;
+                if 0 <= y1 && y1 < self.clamped_height {
+                    unsafe {
+                        self.set_pixel_unchecked_raw_i32(x, y1, raw_color);
+                    }
+                }
+                if 0 <= y2 && y2 < self.clamped_height {
+                    unsafe {
+                        self.set_pixel_unchecked_raw_i32(x, y2, raw_color);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renders the outline of an ellipse shaped region in this [`Canvas`]. The radii must be positive.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.outline_ellipse(8, 8, 8, 4, Color::RED);
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    pub fn outline_ellipse(&mut self, x: i32, y: i32, a: i32, b: i32, color: impl Into<Color>) {
+        if a < 1 || b < 1 {
+            return;
+        }
+
+        let raw_color = u32::from(color.into());
+
+        let mut i = -a;
+        let mut j = 0;
+
+        // change to larger integers to avoid overflow.
+        let b2 = i64::from(b) * i64::from(b);
+        let a2 = i64::from(a) * i64::from(a);
+        let mut err = i64::from(i) * (2 * b2 + i64::from(i)) + b2;
+
+        loop {
+            let x1 = x - i;
+            let x2 = x + i;
+            let y1 = y - j;
+            let y2 = y + j;
+
+            // TODO: benchmark this with precise tooling against just using self.set_pixel()
+            // flamegraph shows a siginificant difference, but I'm not convinced.
+            // on the axes, the mirrored points coincide
+            if 0 <= x1 && x1 < self.clamped_width {
+                if 0 <= y1 && y1 < self.clamped_height {
+                    unsafe {
+                        self.set_pixel_unchecked_raw_i32(x1, y1, raw_color);
+                    }
+                }
+                if j != 0 && 0 <= y2 && y2 < self.clamped_height {
+                    unsafe {
+                        self.set_pixel_unchecked_raw_i32(x1, y2, raw_color);
+                    }
+                }
+            }
+            if i != 0 && 0 <= x2 && x2 < self.clamped_width {
+                if 0 <= y1 && y1 < self.clamped_height {
+                    unsafe {
+                        self.set_pixel_unchecked_raw_i32(x2, y1, raw_color);
+                    }
+                }
+                if j != 0 && 0 <= y2 && y2 < self.clamped_height {
+                    unsafe {
+                        self.set_pixel_unchecked_raw_i32(x2, y2, raw_color);
+                    }
+                }
+            }
+
+            let e2 = 2 * err;
+            if e2 >= i64::from(i * 2 + 1) * b2 {
+                i += 1;
+                err += i64::from(i * 2 + 1) * b2;
+            }
+
+            if e2 <= i64::from(j * 2 + 1) * a2 {
+                j += 1;
+                err += i64::from(j * 2 + 1) * a2;
+            }
+
+            if i > 0 {
+                break;
+            }
+        }
+
+        while j < b {
+            j += 1;
+            if 0 <= x && x < self.clamped_width {
+                let y1 = y + j;
+                let y2 = y - j;
+                if 0 <= y1 && y1 < self.clamped_height {
+                    unsafe {
+                        self.set_pixel_unchecked_raw_i32(x, y1, raw_color);
+                    }
+                }
+                if 0 <= y2 && y2 < self.clamped_height {
+                    unsafe {
+                        self.set_pixel_unchecked_raw_i32(x, y2, raw_color);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renders a triangle in this [`Canvas`].
+    /// Pixels exactly on an edge follow the top-left rule: they are only filled if they lie on a top or a left edge.
+    /// Thus triangles sharing an edge never fill the same pixel twice and leave no gaps between them.
+    /// A degenerate triangle (with collinear vertices) is rendered as a line.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.fill_triangle(1, 0, 12, 0, 13, 15, Color::RED);
+    /// ```
+    #[allow(
+        clippy::too_many_arguments,
+        clippy::cast_sign_loss,
+        clippy::cast_possible_truncation
+    )]
+    pub fn fill_triangle(
+        &mut self,
+        x1: i32,
+        y1: i32,
+        mut x2: i32,
+        mut y2: i32,
+        mut x3: i32,
+        mut y3: i32,
+        color: impl Into<Color>,
+    ) {
+        use std::mem::swap;
+        let raw_color = u32::from(color.into());
+
+        // Make the winding consistent, so the inside of every edge is where its edge function is positive.
+        let delta = |a: i32, b: i32| i128::from(b) - i128::from(a);
+        let area = delta(x1, x2) * delta(y1, y3) - delta(y1, y2) * delta(x1, x3);
+        if area == 0 {
+            // the vertices are collinear, the triangle is the line between the two farthest apart
+            let distance = |(ax, ay), (bx, by)| delta(ax, bx).pow(2) + delta(ay, by).pow(2);
+            let sides = [
+                ((x1, y1), (x2, y2)),
+                ((x2, y2), (x3, y3)),
+                ((x3, y3), (x1, y1)),
+            ];
+            if let Some(&((ax, ay), (bx, by))) = sides.iter().max_by_key(|&&(a, b)| distance(a, b))
+            {
+                self.line(ax, ay, bx, by, raw_color);
+            }
+            return;
+        } else if area < 0 {
+            swap(&mut x2, &mut x3);
+            swap(&mut y2, &mut y3);
+        }
+
+        let edges = [
+            Edge::new(x1, y1, x2, y2),
+            Edge::new(x2, y2, x3, y3),
+            Edge::new(x3, y3, x1, y1),
+        ];
+
+        let from_y = y1.min(y2).min(y3).max(0);
+        let to_y = y1.max(y2).max(y3).min(self.clamped_height - 1);
+
+        for y in from_y..=to_y {
+            let mut from_x = 0;
+            let mut to_x = i64::from(self.clamped_width) - 1;
+
+            for edge in &edges {
+                let (min, max) = edge.span(y);
+                from_x = from_x.max(min);
+                to_x = to_x.min(max);
+            }
+
+            if from_x <= to_x {
+                let offset = y as usize * self.width;
+                self.fill_span_raw(
+                    offset + from_x as usize..offset + to_x as usize + 1,
+                    raw_color,
+                );
+            }
+        }
+    }
+
+    /// Renders the outline of a triangle in this [`Canvas`].
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.outline_triangle(1, 0, 12, 0, 13, 15, Color::RED);
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn outline_triangle(
+        &mut self,
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        x3: i32,
+        y3: i32,
+        color: impl Into<Color>,
+    ) {
+        let raw_color = u32::from(color.into());
+
+        self.stroke_path(&[(x1, y1), (x2, y2), (x3, y3)], true, 1, raw_color);
+    }
+
+    /// Renders the outline of a triangle with thickness in this [`Canvas`]. Joints are covered by rounded ends (circles).
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.thick_outline_triangle(1, 0, 12, 0, 13, 15, 3, Color::RED);
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn thick_outline_triangle(
+        &mut self,
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        x3: i32,
+        y3: i32,
+        thickness: i32,
+        color: impl Into<Color>,
+    ) {
+        if thickness < 0 {
+            return;
+        } else if thickness == 1 {
+            self.outline_triangle(x1, y1, x2, y2, x3, y3, color);
+            return;
+        }
+
+        let raw_color = u32::from(color.into());
+
+        self.stroke_path(&[(x1, y1), (x2, y2), (x3, y3)], true, thickness, raw_color);
+    }
+
+    /// Fills a closed polygon (concave and self-intersecting ones included) in this [`Canvas`].
+    /// The last point is connected to the first one, and the inside is determined by the even-odd rule.
+    /// Pixels are sampled at their centers; like with [`fill_triangle`](struct.Canvas.html#method.fill_triangle),
+    /// pixels exactly on a left or top edge are inside, pixels on a right or bottom edge are not,
+    /// so polygons sharing an edge do not overlap. Nothing happens if there are fewer than 3 points.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    ///
+    /// // an arrow pointing right
+    /// canvas.fill_polygon(&[(0, 5), (8, 5), (8, 0), (16, 8), (8, 16), (8, 11), (0, 11)], Color::RED);
+    ///
+    /// assert_eq!(buffer[8 * 16 + 2], Color::RED.0);
+    /// assert_eq!(buffer[8 * 16 + 14], Color::RED.0);
+    /// assert_eq!(buffer[2 * 16 + 2], 0);
+    /// ```
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn fill_polygon<P: Into<Point> + Copy>(&mut self, points: &[P], color: impl Into<Color>) {
+        if points.len() < 3 {
+            return;
+        }
+
+        let points = point_tuples(points);
+        let raw_color = u32::from(color.into());
+
+        let edges: Vec<_> = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .filter(|(a, b)| a.1 != b.1)
+            .map(|(&(x1, y1), &(x2, y2))| {
+                // always pointing downwards
+                if y1 < y2 {
+                    (
+                        i128::from(x1),
+                        i128::from(y1),
+                        i128::from(x2),
+                        i128::from(y2),
+                    )
+                } else {
+                    (
+                        i128::from(x2),
+                        i128::from(y2),
+                        i128::from(x1),
+                        i128::from(y1),
+                    )
+                }
+            })
+            .collect();
+
+        let min_y = points.iter().map(|p| p.1).min().unwrap_or(0).max(0);
+        let max_y = points
+            .iter()
+            .map(|p| p.1)
+            .max()
+            .unwrap_or(0)
+            .min(self.clamped_height);
+
+        let mut crossings = Vec::new();
+        for y in min_y..max_y {
+            let row = i128::from(y);
+
+            // the first pixel at or right of where each edge crosses the row
+            crossings.clear();
+            crossings.extend(
+                edges
+                    .iter()
+                    .filter(|(_, y1, _, y2)| *y1 <= row && row < *y2)
+                    .map(|&(x1, y1, x2, y2)| {
+                        let (num, den) = (x1 * (y2 - y1) + (row - y1) * (x2 - x1), y2 - y1);
+                        -(-num).div_euclid(den)
+                    }),
+            );
+            crossings.sort_unstable();
+
+            for pair in crossings.chunks_exact(2) {
+                let from_x = pair[0].clamp(0, i128::from(self.clamped_width)) as i32;
+                let to_x = pair[1].clamp(0, i128::from(self.clamped_width)) as i32;
+                if from_x < to_x {
+                    self.hline(y, from_x, to_x - 1, raw_color);
+                }
+            }
+        }
+    }
+
+    /// Fills a regular polygon in this [`Canvas`] whose vertices lie on a circle of radius `r` around (x, y).
+    /// The first vertex is at angle `rotation` (in radians, clockwise from the positive x axis, as y points down).
+    /// Nothing happens if `sides` is less than 3 or the radius is less than 1.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    ///
+    /// // a hexagon with a flat top
+    /// canvas.fill_regular_polygon(8, 8, 6, 6, 0.0, Color::GREEN);
+    ///
+    /// assert_eq!(buffer[8 * 16 + 8], Color::GREEN.0);
+    /// assert_eq!(buffer[8 * 16 + 2], Color::GREEN.0);
+    /// assert_eq!(buffer[3 * 16 + 3], 0);
+    /// ```
+    pub fn fill_regular_polygon(
+        &mut self,
+        x: i32,
+        y: i32,
+        r: i32,
+        sides: u32,
+        rotation: f32,
+        color: impl Into<Color>,
+    ) {
+        if let Some(vertices) = regular_polygon_vertices(x, y, r, sides, rotation) {
+            self.fill_polygon(&vertices, color);
+        }
+    }
+
+    /// Renders the outline of a regular polygon in this [`Canvas`], see [`fill_regular_polygon`](struct.Canvas.html#method.fill_regular_polygon).
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    ///
+    /// // a diamond
+    /// canvas.outline_regular_polygon(8, 8, 6, 4, 0.0, Color::RED);
+    ///
+    /// assert_eq!(buffer[8 * 16 + 14], Color::RED.0);
+    /// assert_eq!(buffer[2 * 16 + 8], Color::RED.0);
+    /// assert_eq!(buffer[8 * 16 + 8], 0);
+    /// ```
+    pub fn outline_regular_polygon(
+        &mut self,
+        x: i32,
+        y: i32,
+        r: i32,
+        sides: u32,
+        rotation: f32,
+        color: impl Into<Color>,
+    ) {
+        if let Some(vertices) = regular_polygon_vertices(x, y, r, sides, rotation) {
+            self.polygon_outline(&vertices, color);
+        }
+    }
+
+    /// Renders connected line segments through `points` in this [`Canvas`].
+    /// The points can be [`Point`]s, `(i32, i32)` tuples or anything else that converts into a [`Point`].
+    /// Nothing happens if there are fewer than 2 points.
+    /// ``` rust
+    /// use vason::{Canvas, Color, Point};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    ///
+    /// // a zigzag chart
+    /// canvas.polyline(&[(0, 15), (5, 3), (10, 12), (15, 0)], Color::RED);
+    /// // a diagonal
+    /// canvas.polyline(&[Point::new(0, 0), Point::new(15, 15)], Color::BLUE);
+    ///
+    /// assert_eq!(buffer[3 * 16 + 5], Color::RED.0);
+    /// assert_eq!(buffer[12 * 16 + 10], Color::RED.0);
+    /// assert_eq!(buffer[4 * 16 + 4], Color::BLUE.0);
+    /// ```
+    pub fn polyline<P: Into<Point> + Copy>(&mut self, points: &[P], color: impl Into<Color>) {
+        let raw_color = u32::from(color.into());
+        self.stroke_path(&point_tuples(points), false, 1, raw_color);
+    }
+
+    /// Renders the outline of a closed polygon in this [`Canvas`]: like [`polyline`](struct.Canvas.html#method.polyline),
+    /// but the last point is connected back to the first one (if there are at least 3 points).
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.polygon_outline(&[(1, 1), (14, 1), (8, 14)], Color::RED);
+    ///
+    /// assert_eq!(buffer[14 * 16 + 8], Color::RED.0);
+    /// ```
+    pub fn polygon_outline<P: Into<Point> + Copy>(
+        &mut self,
+        points: &[P],
+        color: impl Into<Color>,
+    ) {
+        let raw_color = u32::from(color.into());
+        self.stroke_path(&point_tuples(points), points.len() >= 3, 1, raw_color);
+    }
+
+    /// Renders connected line segments with thickness through `points` in this [`Canvas`].
+    /// Interior joints are covered by rounded ends (circles), like in [`thick_outline_triangle`](struct.Canvas.html#method.thick_outline_triangle).
+    /// Nothing happens if there are fewer than 2 points.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.thick_polyline(&[(1, 14), (8, 2), (14, 14)], 3, Color::RED);
+    ///
+    /// assert_eq!(buffer[2 * 16 + 8], Color::RED.0);
+    /// ```
+    pub fn thick_polyline<P: Into<Point> + Copy>(
+        &mut self,
+        points: &[P],
+        thickness: i32,
+        color: impl Into<Color>,
+    ) {
+        if thickness < 0 {
+            return;
+        } else if thickness == 1 {
+            self.polyline(points, color);
+            return;
+        }
+
+        let raw_color = u32::from(color.into());
+        self.stroke_path(&point_tuples(points), false, thickness, raw_color);
+    }
+
+    /// Renders a quadratic Bézier curve from (x0, y0) to (x1, y1) with the control point (cx, cy) in this [`Canvas`].
+    /// The curve is adaptively subdivided until it is flat enough and then stroked with [`line`](struct.Canvas.html#method.line).
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.quadratic_bezier(0, 15, 8, -15, 15, 15, Color::RED);
+    ///
+    /// assert_eq!(buffer[15 * 16], Color::RED.0);
+    /// assert_eq!(buffer[15 * 16 + 15], Color::RED.0);
+    /// // the apex lies halfway between the endpoints and the control point
+    /// assert_eq!(buffer[8], Color::RED.0);
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn quadratic_bezier(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        cx: i32,
+        cy: i32,
+        x1: i32,
+        y1: i32,
+        color: impl Into<Color>,
+    ) {
+        let points = flatten_quadratic_bezier((x0, y0), (cx, cy), (x1, y1));
+        self.polyline(&points, color);
+    }
+
+    /// Renders a quadratic Bézier curve with thickness in this [`Canvas`].
+    /// See [`quadratic_bezier`](struct.Canvas.html#method.quadratic_bezier) and [`thick_polyline`](struct.Canvas.html#method.thick_polyline).
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.thick_quadratic_bezier(2, 13, 8, -7, 13, 13, 3, Color::RED);
+    ///
+    /// assert_eq!(buffer[3 * 16 + 8], Color::RED.0);
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn thick_quadratic_bezier(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        cx: i32,
+        cy: i32,
+        x1: i32,
+        y1: i32,
+        thickness: i32,
+        color: impl Into<Color>,
+    ) {
+        let points = flatten_quadratic_bezier((x0, y0), (cx, cy), (x1, y1));
+        self.thick_polyline(&points, thickness, color);
+    }
+
+    /// Renders a cubic Bézier curve from (x0, y0) to (x1, y1) with the control points (c1x, c1y) and (c2x, c2y) in this [`Canvas`].
+    /// The curve is adaptively subdivided until it is flat enough and then stroked with [`line`](struct.Canvas.html#method.line).
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// // an S shaped curve
+    /// canvas.cubic_bezier(0, 0, 20, 0, -5, 15, 15, 15, Color::RED);
+    ///
+    /// assert_eq!(buffer[0], Color::RED.0);
+    /// assert_eq!(buffer[15 * 16 + 15], Color::RED.0);
+    /// // the curve is point symmetric around its middle
+    /// assert_eq!(buffer[7 * 16 + 7] | buffer[8 * 16 + 8], Color::RED.0);
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn cubic_bezier(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        c1x: i32,
+        c1y: i32,
+        c2x: i32,
+        c2y: i32,
+        x1: i32,
+        y1: i32,
+        color: impl Into<Color>,
+    ) {
+        let points = flatten_cubic_bezier([x0, y0, c1x, c1y, c2x, c2y, x1, y1].map(f64::from));
+        self.polyline(&points, color);
+    }
+
+    /// Renders a cubic Bézier curve with thickness in this [`Canvas`].
+    /// See [`cubic_bezier`](struct.Canvas.html#method.cubic_bezier) and [`thick_polyline`](struct.Canvas.html#method.thick_polyline).
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.thick_cubic_bezier(2, 2, 20, 2, -5, 13, 13, 13, 3, Color::RED);
+    ///
+    /// assert_eq!(buffer[2 * 16 + 2], Color::RED.0);
+    /// assert_eq!(buffer[13 * 16 + 13], Color::RED.0);
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn thick_cubic_bezier(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        c1x: i32,
+        c1y: i32,
+        c2x: i32,
+        c2y: i32,
+        x1: i32,
+        y1: i32,
+        thickness: i32,
+        color: impl Into<Color>,
+    ) {
+        let points = flatten_cubic_bezier([x0, y0, c1x, c1y, c2x, c2y, x1, y1].map(f64::from));
+        self.thick_polyline(&points, thickness, color);
+    }
+
+    /// Renders a horizontal line. Should be preferred when explicitly drawing horizontal lines.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32;256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.hline(10, 0, 16, Color::RED);
+    /// ```
+    #[allow(clippy::cast_sign_loss)]
+    #[inline]
+    pub fn hline(&mut self, y: i32, x1: i32, x2: i32, color: impl Into<Color>) {
+        let raw_color = u32::from(color.into());
+
+        if 0 <= y && y < self.clamped_height {
+            let (x1, x2) = if x1 > x2 { (x2, x1) } else { (x1, x2) };
+            let from_x = x1.clamp(0, self.clamped_width);
+            let to_x = x2.saturating_add(1).clamp(from_x, self.clamped_width);
+            let offset = y as usize * self.width;
+            let range = offset + from_x as usize..offset + to_x as usize;
+            self.fill_span_raw(range, raw_color);
+        }
+    }
+
+    /// Renders a vertical line. Should be preferred when explicitly drawing vertical lines.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.vline(10, 0, 16, Color::RED);
+    /// ```
+    #[allow(clippy::cast_sign_loss)]
+    #[inline]
+    pub fn vline(&mut self, x: i32, y1: i32, y2: i32, color: impl Into<Color>) {
+        let raw_color = u32::from(color.into());
+
+        if 0 <= x && x < self.clamped_width {
+            let (y1, y2) = if y1 > y2 { (y2, y1) } else { (y1, y2) };
+
+            let from_y = y1.clamp(0, self.clamped_height);
+            let to_y = y2.saturating_add(1).clamp(from_y, self.clamped_height);
+
+            for y in from_y..to_y {
+                unsafe { self.set_pixel_unchecked_raw_i32(x, y, raw_color) }
+            }
+        }
+    }
+
+    /// Renders a horizontal line with thickness. Should be preferred when explicitly drawing thick horizontal lines.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.thick_hline(10, 0, 16, 2, Color::RED);
+    /// ```
+    #[inline]
+    pub fn thick_hline(
+        &mut self,
+        y: i32,
+        x1: i32,
+        x2: i32,
+        thickness: i32,
+        color: impl Into<Color>,
+    ) {
+        let thickness = thickness.max(0);
+        let (x1, x2) = if x1 > x2 { (x2, x1) } else { (x1, x2) };
+        self.fill_rect(x1, y + thickness / 2, x2 - x1, thickness, color);
+    }
+
+    /// Renders a vertical line with thickness. Should be preferred when explicitly drawing thick vertical lines.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.thick_vline(10, 0, 16, 2, Color::RED);
+    #[inline]
+    pub fn thick_vline(
+        &mut self,
+        x: i32,
+        y1: i32,
+        y2: i32,
+        thickness: i32,
+        color: impl Into<Color>,
+    ) {
+        let thickness = thickness.max(0);
+        let (y1, y2) = if y1 > y2 { (y2, y1) } else { (y1, y2) };
+        self.fill_rect(x - thickness / 2, y1, thickness, y2 - y1, color);
+    }
+
+    /// Renders a line. Should be preferred when mostly drawing non axis-aligned lines.
+    /// If there is a substantial chance of drawing axis-aligned (hline or vline) consider using [`line_maybe_axis_aligned`](struct.Canvas.html#method.line_maybe_axis_aligned) instead
+    /// The coordinates can be of any [`Coord`] type.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.line(10, 2, 10, 12, Color::RED);
+    /// canvas.line(0.0, 0.0, 15.0, 7.5, Color::BLUE);
+    /// ```
+    pub fn line(
+        &mut self,
+        x1: impl Coord,
+        y1: impl Coord,
+        x2: impl Coord,
+        y2: impl Coord,
+        color: impl Into<Color>,
+    ) {
+        let (x1, y1) = (x1.to_i32(), y1.to_i32());
+        let (x2, y2) = (x2.to_i32(), y2.to_i32());
+        let raw_color = u32::from(color.into());
+
+        self.walk_line(x1, y1, x2, y2, |canvas, x, y, _| {
+            if 0 <= x && x < canvas.clamped_width && 0 <= y && y < canvas.clamped_height {
+                unsafe {
+                    canvas.set_pixel_unchecked_raw_i32(x, y, raw_color);
+                }
+            }
+        });
+    }
+
+    /// Renders a dashed line: `on` pixels are drawn, then `off` pixels are skipped, and so on.
+    /// The pattern always starts with a dash at (x1, y1) and follows the steps of [`line`](struct.Canvas.html#method.line),
+    /// so it depends only on the end points. To continue the pattern of a previous segment, use
+    /// [`dashed_line_with_phase`](struct.Canvas.html#method.dashed_line_with_phase).
+    /// Nothing happens if `on` is not positive, a negative `off` is treated as 0.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.dashed_line(0, 4, 15, 4, 3, 2, Color::RED);
+    ///
+    /// let row: Vec<_> = buffer[4 * 16..5 * 16].iter().map(|&p| p != 0).collect();
+    /// assert_eq!(row[..8], [true, true, true, false, false, true, true, true]);
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn dashed_line(
+        &mut self,
+        x1: impl Coord,
+        y1: impl Coord,
+        x2: impl Coord,
+        y2: impl Coord,
+        on: i32,
+        off: i32,
+        color: impl Into<Color>,
+    ) {
+        self.dashed_line_with_phase(x1, y1, x2, y2, on, off, 0, color);
+    }
+
+    /// Renders a dashed line like [`dashed_line`](struct.Canvas.html#method.dashed_line), with (x1, y1) at step `phase` of the pattern
+    /// instead of at its start. Returns the phase of (x2, y2): passing it to the next segment of a path, which starts there,
+    /// continues the pattern as if the path was one line (the shared point is at the same step in both calls, so both or neither draw it).
+    /// Nothing happens (and 0 is returned) if `on` is not positive.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    ///
+    /// // a dashed path around a corner
+    /// let phase = canvas.dashed_line_with_phase(0, 0, 12, 0, 3, 2, 0, Color::RED);
+    /// assert_eq!(phase, 2);
+    /// canvas.dashed_line_with_phase(12, 0, 12, 15, 3, 2, phase, Color::RED);
+    ///
+    /// let column: Vec<_> = (0..5).map(|y| buffer[y * 16 + 12] != 0).collect();
+    /// assert_eq!(column, [true, false, false, true, true]);
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn dashed_line_with_phase(
+        &mut self,
+        x1: impl Coord,
+        y1: impl Coord,
+        x2: impl Coord,
+        y2: impl Coord,
+        on: i32,
+        off: i32,
+        phase: u64,
+        color: impl Into<Color>,
+    ) -> u64 {
+        if on <= 0 {
+            return 0;
+        }
+
+        let (x1, y1) = (x1.to_i32(), y1.to_i32());
+        let (x2, y2) = (x2.to_i32(), y2.to_i32());
+        let raw_color = u32::from(color.into());
+        let period = u64::from(on.unsigned_abs()) + u64::from(off.max(0).unsigned_abs());
+        let phase = phase % period;
+
+        let mut end_phase = phase;
+        self.walk_line(x1, y1, x2, y2, |canvas, x, y, step| {
+            end_phase = (phase + step) % period;
+            if end_phase < u64::from(on.unsigned_abs())
+                && 0 <= x
+                && x < canvas.clamped_width
+                && 0 <= y
+                && y < canvas.clamped_height
+            {
+                unsafe {
+                    canvas.set_pixel_unchecked_raw_i32(x, y, raw_color);
+                }
+            }
+        });
+
+        end_phase
+    }
+
+    /// Renders a dotted line: single pixels separated by `gap` skipped pixels. Same as [`dashed_line`](struct.Canvas.html#method.dashed_line) with `on` = 1.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.dotted_line(0, 0, 15, 15, 1, Color::RED);
+    ///
+    /// assert_eq!(buffer[0], Color::RED.0);
+    /// assert_eq!(buffer[16 + 1], 0);
+    /// assert_eq!(buffer[2 * 16 + 2], Color::RED.0);
+    /// ```
+    pub fn dotted_line(
+        &mut self,
+        x1: impl Coord,
+        y1: impl Coord,
+        x2: impl Coord,
+        y2: impl Coord,
+        gap: i32,
+        color: impl Into<Color>,
+    ) {
+        self.dashed_line(x1, y1, x2, y2, 1, gap, color);
+    }
+
+    /// Renders a line between floating point coordinates.
+    /// The end points are rounded to the nearest integer, halfway cases away from zero (like [`f32::round`]).
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.line_f32(0.4, 2.6, 10.5, 2.6, Color::RED);
+    ///
+    /// assert_eq!(buffer[3 * 16], Color::RED.0);
+    /// assert_eq!(buffer[3 * 16 + 11], Color::RED.0);
+    /// ```
+    #[inline]
+    pub fn line_f32(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, color: impl Into<Color>) {
+        self.line(x1, y1, x2, y2, color);
+    }
+
+    /// Renders a line. Should be preferred when mostly drawing axis-aligned lines.
+    /// If it is not very likely you'll draw a lot of axis-aligned lines prefer [`line`](struct.Canvas.html#method.line) instead.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16,16);
+    /// // axis aligned
+    /// canvas.line_maybe_axis_aligned(10, 2, 10, 12, Color::RED);
+    /// // not axis aligned
+    /// canvas.line_maybe_axis_aligned(12, 4, 5, 7, Color::BLUE);
+    /// ```
+    #[inline]
+    pub fn line_maybe_axis_aligned(
+        &mut self,
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        color: impl Into<Color>,
+    ) {
+        if x1 == x2 {
+            self.vline(x1, y1, y2, color);
+        } else if y1 == y2 {
+            self.hline(y1, x1, x2, color);
+        } else {
+            self.line(x1, y1, x2, y2, color);
+        }
+    }
+
+    /// Renders a line with thickness. Should be preferred when mostly drawing non axis-aligned lines.
+    /// If there is a substantial chance of drawing axis-aligned (hline or vline) consider using [`thick_line_maybe_axis_aligned`](struct.Canvas.html#method.thick_line_maybe_axis_aligned) instead
+    /// The line is rendered as a quad, so the stroke width is uniform (within a pixel) regardless of the slope. The ends are flat.
+    /// A thickness of 0 or 1 renders a plain [`line`](struct.Canvas.html#method.line), a negative thickness renders nothing.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.thick_line(10, 2, 10, 12, 4, Color::RED);
+    /// ```
+    #[allow(clippy::similar_names, clippy::cast_possible_truncation)]
+    pub fn thick_line(
+        &mut self,
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        thickness: i32,
+        color: impl Into<Color>,
+    ) {
+        if thickness < 0 {
+            return;
+        } else if thickness <= 1 {
+            self.line(x1, y1, x2, y2, color);
+            return;
+        }
+
+        let raw_color = u32::from(color.into());
+
+        if x1 == x2 && y1 == y2 {
+            let half_thickness = thickness / 2;
+            self.fill_rect(
+                x1 - half_thickness,
+                y1 - half_thickness,
+                thickness,
+                thickness,
+                raw_color,
+            );
+            return;
+        }
+
+        let dx = f64::from(x2 - x1);
+        let dy = f64::from(y2 - y1);
+        let length = (dx * dx + dy * dy).sqrt();
+
+        // unit direction and unit normal of the line
+        let (ux, uy) = (dx / length, dy / length);
+        let (nx, ny) = (-uy, ux);
+
+        // A pixel (x, y) is covered if its distance along the line from (x1, y1) is within [0, length]
+        // and its signed distance from the line is within [-thickness / 2, thickness / 2).
+        // The upper bound is made exclusive so axis-aligned lines are exactly `thickness` wide.
+        let half_thickness = f64::from(thickness) * 0.5;
+        let along = (0.0, length);
+        let across = (-half_thickness, half_thickness - 1e-9);
+
+        let corners_y = [
+            f64::from(y1) + ny * across.0,
+            f64::from(y1) + ny * across.1,
+            f64::from(y2) + ny * across.0,
+            f64::from(y2) + ny * across.1,
+        ];
+        let min_y = corners_y.iter().copied().fold(f64::INFINITY, f64::min);
+        let max_y = corners_y.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        let from_y = (min_y.ceil() as i32).max(0);
+        let to_y = (max_y.floor() as i32).min(self.clamped_height - 1);
+
+        for y in from_y..=to_y {
+            let ry = f64::from(y - y1);
+            let (a_from, a_to) = Self::solve_span(ux, uy * ry, along);
+            let (b_from, b_to) = Self::solve_span(nx, ny * ry, across);
+
+            let from_x = a_from.max(b_from).ceil();
+            let to_x = a_to.min(b_to).floor();
+
+            if from_x <= to_x {
+                // hline does the horizontal clipping
+                let from_x = (f64::from(x1) + from_x).max(f64::from(i32::MIN)) as i32;
+                let to_x = (f64::from(x1) + to_x).min(f64::from(self.clamped_width)) as i32;
+                if to_x >= 0 && from_x < self.clamped_width {
+                    self.hline(y, from_x, to_x, raw_color);
+                }
+            }
+        }
+    }
+
+    // Returns the range of x for which `lo <= coef * x + constant <= hi` holds.
+    #[inline]
+    fn solve_span(coef: f64, constant: f64, (lo, hi): (f64, f64)) -> (f64, f64) {
+        if coef.abs() < 1e-12 {
+            if lo <= constant && constant <= hi {
+                (f64::NEG_INFINITY, f64::INFINITY)
+            } else {
+                (f64::INFINITY, f64::NEG_INFINITY)
+            }
+        } else {
+            let a = (lo - constant) / coef;
+            let b = (hi - constant) / coef;
+            (a.min(b), a.max(b))
+        }
+    }
+
+    /// Renders a line with thickness. Should be preferred when mostly drawing axis-aligned lines.
+    /// If it is not very likely you'll draw a lot of axis-aligned lines prefer [`thick_line`](struct.Canvas.html#method.thick_line) instead.
+    /// ``` rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16,16);
+    /// // axis aligned
+    /// canvas.thick_line_maybe_axis_aligned(10, 2, 10, 12, 3, Color::RED);
+    /// // not axis aligned
+    /// canvas.thick_line_maybe_axis_aligned(12, 4, 5, 7, 4, Color::BLUE);
+    /// ```
+    #[inline]
+    pub fn thick_line_maybe_axis_aligned(
+        &mut self,
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        thickness: i32,
+        color: impl Into<Color>,
+    ) {
+        if x1 == x2 {
+            self.thick_vline(x1, y1, y2, thickness, color);
+        } else if y1 == y2 {
+            self.thick_hline(y1, x1, x2, thickness, color);
+        } else {
+            self.thick_line(x1, y1, x2, y2, thickness, color);
+        }
+    }
+
+    /// Renders text with the built-in 8x8 monospace [`font`](crate::font), (x, y) being the top left corner of the first glyph.
+    /// Every character advances the cursor by 8 pixels, `'\n'` starts a new line 8 pixels below at x.
+    /// Characters outside of ASCII are drawn as a hollow box.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 32 * 16];
+    /// let mut canvas = Canvas::new(&mut buffer, 32, 16);
+    /// canvas.draw_text(0, 0, "Hi!\nok", Color::WHITE);
+    ///
+    /// // the top left pixel of the 'H'
+    /// assert_eq!(buffer[1], Color::WHITE.0);
+    /// ```
+    pub fn draw_text(&mut self, x: i32, y: i32, text: &str, color: impl Into<Color>) {
+        self.draw_text_with_font(x, y, text, &Font::MONOSPACE, color);
+    }
+
+    /// Renders text with the supplied [`Font`], (x, y) being the top left corner of the first glyph.
+    /// Every character advances the cursor by its advance in the font, `'\n'` starts a new line 8 pixels below at x.
+    /// The area covered matches [`Font::measure_text`].
+    /// ```rust
+    /// use vason::{font::Font, Canvas, Color};
+    /// let mut buffer = [0u32; 64 * 8];
+    /// let mut canvas = Canvas::new(&mut buffer, 64, 8);
+    /// let font = Font::proportional();
+    /// canvas.draw_text_with_font(0, 0, "Hello", &font, Color::WHITE);
+    /// ```
+    pub fn draw_text_with_font(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        font: &Font,
+        color: impl Into<Color>,
+    ) {
+        let raw_color = u32::from(color.into());
+        let (mut cx, mut cy) = (x, y);
+
+        for ch in text.chars() {
+            if ch == '\n' {
+                cx = x;
+                cy = cy.saturating_add(font::GLYPH_HEIGHT);
+                continue;
+            }
+
+            self.draw_glyph(cx, cy, font.glyph(ch), raw_color);
+            cx = cx.saturating_add(font.advance(ch));
+        }
+    }
+
+    /// Renders text with the built-in font, breaking it into lines at word boundaries so it fits within `max_width` pixels.
+    /// See [`Font::wrap_text`] for how lines are broken; use it with [`Font::measure_text`] to know the area covered beforehand.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 80 * 32];
+    /// let mut canvas = Canvas::new(&mut buffer, 80, 32);
+    /// canvas.draw_text_wrapped(0, 0, 80, "the quick brown fox", Color::WHITE);
+    /// ```
+    pub fn draw_text_wrapped(
+        &mut self,
+        x: i32,
+        y: i32,
+        max_width: i32,
+        text: &str,
+        color: impl Into<Color>,
+    ) {
+        let font = &Font::MONOSPACE;
+        self.draw_text_with_font(x, y, &font.wrap_text(text, max_width), font, color);
+    }
+
+    /// Renders text with the built-in font rotated by `angle_deg` degrees clockwise around (x, y),
+    /// the top left corner of the first glyph. At 0 degrees this is the same as [`draw_text`](struct.Canvas.html#method.draw_text),
+    /// at 90 degrees the text runs top to bottom (and at -90 bottom to top, e.g. for y-axis labels).
+    /// Glyphs are rotated as a whole, so `'\n'` starts a new line perpendicular to the text direction.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 16 * 32];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 32);
+    /// canvas.draw_text_rotated(8, 0, "Hi", 90.0, Color::WHITE);
+    ///
+    /// // the top left pixel of the 'H' ends up at the top right
+    /// assert_eq!(buffer[7 * 16 + 1], 0);
+    /// assert_eq!(buffer[1 * 16 + 7], Color::WHITE.0);
+    /// ```
+    pub fn draw_text_rotated(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        angle_deg: f32,
+        color: impl Into<Color>,
+    ) {
+        if angle_deg == 0.0 {
+            self.draw_text(x, y, text, color);
+            return;
+        }
+
+        let raw_color = u32::from(color.into());
+        let font = &Font::MONOSPACE;
+        let (sin, cos) = f64::from(angle_deg).to_radians().sin_cos();
+        let (mut u, mut v) = (0, 0);
+
+        for ch in text.chars() {
+            if ch == '\n' {
+                u = 0;
+                v += font::GLYPH_HEIGHT;
+                continue;
+            }
+
+            self.draw_glyph_rotated(x, y, (u, v), (sin, cos), font.glyph(ch), raw_color);
+            u += font.advance(ch);
+        }
+    }
+
+    /// Starts a flood fill from supplied coordinate filling the area with the color provided.
+    /// The fill is 4-connected and uses an explicit work stack, so arbitrarily large areas can be filled.
+    /// Returns the number of pixels filled, which is 0 if the seed is outside of the canvas or already has the color.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.outline_rect(2, 2, 10, 10, Color::WHITE);
+    ///
+    /// assert_eq!(canvas.flood_fill(5, 5, Color::RED), 64);
+    /// assert_eq!(canvas.flood_fill(5, 5, Color::RED), 0);
+    /// ```
+    pub fn flood_fill(&mut self, x: i32, y: i32, color: impl Into<Color>) -> usize {
+        let bounds = (0, self.width, 0, self.height);
+        self.flood_fill_within(x, y, color.into(), bounds)
+    }
+
+    /// Starts an 8-connected flood fill from supplied coordinate filling the area with the color provided.
+    /// Unlike [`flood_fill`](struct.Canvas.html#method.flood_fill), the fill also spreads to diagonal neighbours,
+    /// so it fills thin diagonal regions (and leaks through diagonal gaps of 4-connected borders, such as the ones of lines).
+    /// Returns the number of pixels filled.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 16];
+    /// let mut canvas = Canvas::new(&mut buffer, 4, 4);
+    /// canvas.clear(Color::WHITE);
+    /// // a diagonal line of black pixels
+    /// canvas.line(0, 0, 3, 3, Color::BLACK);
+    ///
+    /// assert_eq!(canvas.flood_fill_8(0, 0, Color::RED), 4);
+    /// assert_eq!(canvas.flood_fill(1, 1, Color::BLACK), 1);
+    /// ```
+    #[allow(clippy::cast_sign_loss)]
+    pub fn flood_fill_8(&mut self, x: i32, y: i32, color: impl Into<Color>) -> usize {
+        if 0 <= x && x < self.clamped_width && 0 <= y && y < self.clamped_height {
+            let raw_color = u32::from(color.into());
+            let (xu, yu) = (x as usize, y as usize);
+            let seed_color = self.buffer[yu * self.width + xu];
+            if seed_color != raw_color {
+                return self.scanline_fill_8(xu, yu, raw_color, |p| p == seed_color);
+            }
+        }
+
+        0
+    }
+
+    /// Starts a flood fill from supplied coordinate that spreads (4-connected) into every pixel whose color satisfies `predicate`,
+    /// instead of only the ones with the color of the seed. If the seed doesn't satisfy `predicate`, nothing is filled.
+    /// The fill color may satisfy `predicate` as well. Returns the number of pixels filled.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 16];
+    /// let mut canvas = Canvas::new(&mut buffer, 4, 4);
+    /// canvas.clear(Color::WHITE);
+    /// canvas.set_pixel(0, 0, Color::rgb(0, 0, 200));
+    /// canvas.set_pixel(1, 0, Color::rgb(20, 20, 250));
+    /// canvas.set_pixel(1, 1, Color::rgb(0, 40, 120));
+    ///
+    /// // fill everything bluish
+    /// let bluish = |c: Color| {
+    ///     let (r, g, b) = c.to_rgb();
+    ///     b > 100 && r < 50 && g < 50
+    /// };
+    /// assert_eq!(canvas.flood_fill_if(0, 0, Color::BLUE, bluish), 3);
+    /// ```
+    #[allow(clippy::cast_sign_loss)]
+    pub fn flood_fill_if(
+        &mut self,
+        x: i32,
+        y: i32,
+        color: impl Into<Color>,
+        predicate: impl Fn(Color) -> bool,
+    ) -> usize {
+        if 0 <= x && x < self.clamped_width && 0 <= y && y < self.clamped_height {
+            let (xu, yu) = (x as usize, y as usize);
+            if predicate(Color(self.buffer[yu * self.width + xu])) {
+                let raw_color = u32::from(color.into());
+                let bounds = (0, self.width, 0, self.height);
+                // The fill color may satisfy the predicate, so visited pixels are tracked.
+                return self.span_fill(xu, yu, bounds, raw_color, |p| predicate(Color(p)), true);
+            }
+        }
+
+        0
+    }
+
+    /// Starts a flood fill from supplied coordinate, but never fills outside of the clip rectangle given as (x, y, w, h).
+    /// The clip rectangle is clipped to the canvas the same way [`fill_rect`](struct.Canvas.html#method.fill_rect) does.
+    /// If the seed is outside of the clip rectangle, nothing is filled. Returns the number of pixels filled.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    ///
+    /// assert_eq!(canvas.flood_fill_bounded(5, 5, Color::RED, (4, 4, 4, 4)), 16);
+    /// assert_eq!(canvas.buffer().iter().filter(|&&p| p == Color::RED.0).count(), 16);
+    /// ```
+    #[allow(clippy::cast_sign_loss)]
+    pub fn flood_fill_bounded(
+        &mut self,
+        x: i32,
+        y: i32,
+        color: impl Into<Color>,
+        (clip_x, clip_y, clip_w, clip_h): (i32, i32, i32, i32),
+    ) -> usize {
+        if clip_w <= 0 || clip_h <= 0 {
+            return 0;
+        }
+
+        let (from_x, to_x, from_y, to_y) = self.clamp_rect_i32(
+            clip_x,
+            clip_x.saturating_add(clip_w),
+            clip_y,
+            clip_y.saturating_add(clip_h),
+        );
+
+        if from_x <= x && x < to_x && from_y <= y && y < to_y {
+            let bounds = (
+                from_x as usize,
+                to_x as usize,
+                from_y as usize,
+                to_y as usize,
+            );
+            self.flood_fill_within(x, y, color.into(), bounds)
+        } else {
+            0
+        }
+    }
+
+    /// Starts a boundary fill from supplied coordinate: the fill spreads (4-connected) until it reaches pixels with the border color,
+    /// regardless of the colors inside the region. If the seed itself has the border color, nothing is filled.
+    /// Returns the number of pixels filled (including the ones that already had the fill color).
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = [0u32; 256];
+    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
+    /// canvas.outline_rect(2, 2, 10, 10, Color::WHITE);
+    /// canvas.fill_rect(4, 4, 2, 2, Color::BLUE);
+    ///
+    /// assert_eq!(canvas.boundary_fill(3, 3, Color::RED, Color::WHITE), 64);
+    /// assert_eq!(canvas.buffer().iter().filter(|&&p| p == Color::RED.0).count(), 64);
+    /// ```
+    #[allow(clippy::cast_sign_loss)]
+    pub fn boundary_fill(
+        &mut self,
+        x: i32,
+        y: i32,
+        fill_color: impl Into<Color>,
+        border_color: impl Into<Color>,
+    ) -> usize {
+        if 0 <= x && x < self.clamped_width && 0 <= y && y < self.clamped_height {
+            let raw_color = u32::from(fill_color.into());
+            let raw_border = u32::from(border_color.into());
+            let bounds = (0, self.width, 0, self.height);
+            // Pixels that already have the fill color must not stop the fill, so visited pixels are tracked.
+            self.span_fill(
+                x as usize,
+                y as usize,
+                bounds,
+                raw_color,
+                |p| p != raw_border,
+                true,
+            )
+        } else {
+            0
+        }
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn flood_fill_within(
+        &mut self,
+        x: i32,
+        y: i32,
+        color: Color,
+        bounds: (usize, usize, usize, usize),
+    ) -> usize {
+        if 0 <= x && x < self.clamped_width && 0 <= y && y < self.clamped_height {
+            let raw_color = u32::from(color);
+            let xu = x as usize;
+            let yu = y as usize;
+            let seed_color = self.buffer[yu * self.width + xu];
+            if seed_color != raw_color {
+                // filled pixels no longer match the seed color, there is no need to track them.
+                return self.span_fill(xu, yu, bounds, raw_color, |p| p == seed_color, false);
+            }
+        }
+
+        0
+    }
+
+    // Span based seed fill (Heckbert, "A Seed Fill Algorithm", Graphics Gems I).
+    // Every entry of the stack is a span (x1..=x2) on row y whose pixels still have to be examined,
+    // together with the direction (dy) the span was discovered from.
+    // Pixels outside of bounds (from_x, to_x, from_y, to_y) are never examined.
+    // A pixel is filled if `inside` returns true for its color. Unless `track_visited` is set,
+    // `inside` must return false for `raw_color`, otherwise the fill would never stop.
+    // Returns the number of pixels filled.
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+    fn span_fill(
+        &mut self,
+        x: usize,
+        y: usize,
+        (from_x, to_x, from_y, to_y): (usize, usize, usize, usize),
+        raw_color: u32,
+        inside: impl Fn(u32) -> bool,
+        track_visited: bool,
+    ) -> usize {
+        let mut target = FillTarget {
+            buffer: &mut *self.buffer,
+            visited: if track_visited {
+                vec![false; self.width * self.height]
+            } else {
+                Vec::new()
+            },
+            raw_color,
+            inside,
+            filled: 0,
+        };
+        let width = self.width;
+
+        let (from_x, to_x) = (from_x as isize, to_x as isize);
+        let (from_y, to_y) = (from_y as isize, to_y as isize);
+
+        let mut stack: Vec<(isize, isize, isize, isize)> = Vec::new();
+        stack.push((x as isize, x as isize, y as isize, 1));
+        stack.push((x as isize, x as isize, y as isize - 1, -1));
+
+        while let Some((mut x1, x2, y, dy)) = stack.pop() {
+            if y < from_y || y >= to_y {
+                continue;
+            }
+
+            let offset = y as usize * width;
+            let mut x = x1;
+
+            if target.is_inside(offset + x as usize) {
+                while x > from_x && target.is_inside(offset + x as usize - 1) {
+                    x -= 1;
+                    target.set(offset + x as usize);
+                }
+                if x < x1 {
+                    stack.push((x, x1 - 1, y - dy, -dy));
+                }
+            }
+
+            while x1 <= x2 {
+                while x1 < to_x && target.is_inside(offset + x1 as usize) {
+                    target.set(offset + x1 as usize);
+                    x1 += 1;
+                }
+                if x1 > x {
+                    stack.push((x, x1 - 1, y + dy, dy));
+                }
+                if x1 - 1 > x2 {
+                    stack.push((x2 + 1, x1 - 1, y - dy, -dy));
+                }
+                x1 += 1;
+                while x1 < x2 && !target.is_inside(offset + x1 as usize) {
+                    x1 += 1;
+                }
+                x = x1;
+            }
+        }
+
+        target.filled
+    }
+
+    // Scanline seed fill with 8-connectivity: every popped seed is extended into a full horizontal run,
+    // which then seeds the runs of inside pixels on the rows above and below, one pixel wider on both sides
+    // to bridge diagonal neighbours. `inside` must return false for `raw_color`.
+    // Returns the number of pixels filled.
+    fn scanline_fill_8(
+        &mut self,
+        x: usize,
+        y: usize,
+        raw_color: u32,
+        inside: impl Fn(u32) -> bool,
+    ) -> usize {
+        let mut target = FillTarget {
+            buffer: &mut *self.buffer,
+            visited: Vec::new(),
+            raw_color,
+            inside,
+            filled: 0,
+        };
+        let (width, height) = (self.width, self.height);
+
+        let mut stack = vec![(x, y)];
+        while let Some((x, y)) = stack.pop() {
+            let offset = y * width;
+            if !target.is_inside(offset + x) {
+                continue;
+            }
+
+            let (mut left, mut right) = (x, x);
+            while left > 0 && target.is_inside(offset + left - 1) {
+                left -= 1;
+            }
+            while right + 1 < width && target.is_inside(offset + right + 1) {
+                right += 1;
+            }
+            for x in left..=right {
+                target.set(offset + x);
+            }
+
+            let (from_x, to_x) = (left.saturating_sub(1), (right + 1).min(width - 1));
+            for ny in [y.wrapping_sub(1), y + 1] {
+                if ny >= height {
+                    continue;
+                }
+
+                let offset = ny * width;
+                let mut in_run = false;
+                for x in from_x..=to_x {
+                    let is_inside = target.is_inside(offset + x);
+                    if is_inside && !in_run {
+                        stack.push((x, ny));
+                    }
+                    in_run = is_inside;
+                }
+            }
+        }
+
+        target.filled
+    }
+
+    #[allow(clippy::similar_names)]
+    #[inline]
+    fn clamp_rect_i32(&self, xmin: i32, xmax: i32, ymin: i32, ymax: i32) -> (i32, i32, i32, i32) {
+        let from_x = xmin.clamp(0, self.clamped_width);
+        let to_x = xmax.clamp(from_x, self.clamped_width);
+
+        let from_y = ymin.clamp(0, self.clamped_height);
+        let to_y = ymax.clamp(from_y, self.clamped_height);
+
+        (from_x, to_x, from_y, to_y)
+    }
+
+    // Fills the union of horizontal spans given as (row, first column, last column), so pixels covered by more than one of them are drawn once.
+    fn fill_span_union(&mut self, mut spans: Vec<(i32, i32, i32)>, raw_color: u32) {
+        spans.sort_unstable();
+
+        let mut current: Option<(i32, i32, i32)> = None;
+        for (row, from, to) in spans {
+            match current {
+                Some((r, f, t)) if r == row && from <= t.saturating_add(1) => {
+                    current = Some((r, f, t.max(to)));
+                }
+                _ => {
+                    if let Some((r, f, t)) = current {
+                        self.hline(r, f, t, raw_color);
+                    }
+                    current = Some((row, from, to));
+                }
+            }
+        }
+        if let Some((r, f, t)) = current {
+            self.hline(r, f, t, raw_color);
+        }
+    }
+
+    // Strokes the segments through `points` (and back to the first one if `closed`) with rounded joints,
+    // drawing every pixel once even where segments and joints overlap.
+    fn stroke_path(&mut self, points: &[(i32, i32)], closed: bool, thickness: i32, raw_color: u32) {
+        if points.len() < 2 {
+            return;
+        }
+
+        let margin = i64::from(thickness.max(1));
+        let (mut xmin, mut xmax, mut ymin, mut ymax) = (i64::MAX, i64::MIN, i64::MAX, i64::MIN);
+        for &(x, y) in points {
+            xmin = xmin.min(i64::from(x) - margin);
+            xmax = xmax.max(i64::from(x) + margin);
+            ymin = ymin.min(i64::from(y) - margin);
+            ymax = ymax.max(i64::from(y) + margin);
+        }
+
+        self.draw_once(
+            (xmin, xmax, ymin, ymax),
+            raw_color,
+            |canvas, dx, dy, raw_color| {
+                let moved: Vec<(i32, i32)> = points
+                    .iter()
+                    .map(|&(x, y)| (x.saturating_add(dx), y.saturating_add(dy)))
+                    .collect();
+                let closing = if closed {
+                    Some([moved[moved.len() - 1], moved[0]])
+                } else {
+                    None
+                };
+
+                for segment in moved.windows(2).chain(closing.as_ref().map(|c| &c[..])) {
+                    let ((x1, y1), (x2, y2)) = (segment[0], segment[1]);
+                    if thickness > 1 {
+                        canvas.thick_line(x1, y1, x2, y2, thickness, raw_color);
+                    } else {
+                        canvas.line(x1, y1, x2, y2, raw_color);
+                    }
+                }
+
+                if thickness > 1 {
+                    let joints = if closed {
+                        &moved[..]
+                    } else {
+                        &moved[1..moved.len() - 1]
+                    };
+                    for &(x, y) in joints {
+                        canvas.fill_circle(x, y, thickness / 2, raw_color);
+                    }
+                }
+            },
+        );
+    }
+
+    // Draws a shape whose parts may overlap so that every covered pixel is written once. Unless redrawing a pixel
+    // is harmless, `draw` renders into an opaque mask covering the inclusive bounds (xmin, xmax, ymin, ymax), given
+    // the offset to add to its coordinates and the color to use; the mask is then applied like a single draw.
+    fn draw_once<F: FnOnce(&mut Canvas, i32, i32, u32)>(
+        &mut self,
+        bounds: (i64, i64, i64, i64),
+        raw_color: u32,
+        draw: F,
+    ) {
+        self.draw_once_except(bounds, raw_color, None, draw);
+    }
+
+    // Strokes a segment of a pen's path from (x1, y1) to (x2, y2): a thick line with rounded ends.
+    // Unless redrawing a pixel is harmless, the pixels in `drawn` (indices into the buffer, covered by
+    // earlier segments of the same path) are left out, and the ones this segment covers are added to it.
+    pub(crate) fn pen_stroke(
+        &mut self,
+        (x1, y1): (i32, i32),
+        (x2, y2): (i32, i32),
+        thickness: i32,
+        raw_color: u32,
+        drawn: &mut HashSet<usize>,
+    ) {
+        let margin = i64::from(thickness.max(1));
+        let bounds = (
+            i64::from(x1.min(x2)) - margin,
+            i64::from(x1.max(x2)) + margin,
+            i64::from(y1.min(y2)) - margin,
+            i64::from(y1.max(y2)) + margin,
+        );
+
+        self.draw_once_except(
+            bounds,
+            raw_color,
+            Some(drawn),
+            |canvas, dx, dy, raw_color| {
+                let (x1, y1) = (x1.saturating_add(dx), y1.saturating_add(dy));
+                let (x2, y2) = (x2.saturating_add(dx), y2.saturating_add(dy));
+
+                canvas.thick_line(x1, y1, x2, y2, thickness, raw_color);
+                if thickness > 1 {
+                    canvas.fill_circle(x1, y1, thickness / 2, raw_color);
+                    canvas.fill_circle(x2, y2, thickness / 2, raw_color);
+                }
+            },
+        );
+    }
+
+    // Like draw_once, but if `drawn` is given, the pixels in it are left out and the covered ones are added to it.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn draw_once_except<F: FnOnce(&mut Canvas, i32, i32, u32)>(
+        &mut self,
+        (xmin, xmax, ymin, ymax): (i64, i64, i64, i64),
+        raw_color: u32,
+        mut drawn: Option<&mut HashSet<usize>>,
+        draw: F,
+    ) {
+        let (raster_op, blend_mode) = (self.raster_op, self.blend_mode);
+        let opaque = blend_mode == BlendMode::Replace || PixelFormat::unpack(raw_color).3 == 255;
+        if raster_op == RasterOp::Copy && opaque {
+            draw(self, 0, 0, raw_color);
+            return;
+        }
+
+        let clamp = |v: i64, max: i32| v.clamp(0, i64::from(max)) as i32;
+        let (from_x, to_x) = (
+            clamp(xmin, self.clamped_width),
+            clamp(xmax.saturating_add(1), self.clamped_width),
+        );
+        let (from_y, to_y) = (
+            clamp(ymin, self.clamped_height),
+            clamp(ymax.saturating_add(1), self.clamped_height),
+        );
+        if from_x >= to_x || from_y >= to_y {
+            return;
+        }
+
+        let (mask_width, mask_height) = ((to_x - from_x) as usize, (to_y - from_y) as usize);
+        let mut mask = vec![0u32; mask_width * mask_height];
+        draw(
+            &mut Canvas::new(&mut mask, mask_width, mask_height),
+            -from_x,
+            -from_y,
+            u32::MAX,
+        );
+
+        for (row, mask_row) in mask.chunks_exact(mask_width).enumerate() {
+            let offset = (from_y as usize + row) * self.width + from_x as usize;
+            for (column, _) in mask_row.iter().enumerate().filter(|(_, &m)| m != 0) {
+                let idx = offset + column;
+                if let Some(drawn) = drawn.as_mut() {
+                    if !drawn.insert(idx) {
+                        continue;
+                    }
+                }
+                self.buffer[idx] = combine(raster_op, blend_mode, self.buffer[idx], raw_color);
+            }
+        }
+    }
+
+    // Fills the pixels in x1..x2 and y1..y2 (excluding x2 and y2).
+    fn fill_rect_between(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, raw_color: u32) {
+        self.fill_rect(
+            x1,
+            y1,
+            x2.saturating_sub(x1),
+            y2.saturating_sub(y1),
+            raw_color,
+        );
+    }
+
+    fn to_linear_pixels(&self) -> Vec<[f32; 4]> {
+        self.buffer
+            .iter()
+            .map(|p| {
+                let (r, g, b, a) = Color(*p).to_linear();
+                [r, g, b, a]
+            })
+            .collect()
+    }
+
+    fn set_linear_pixels(&mut self, pixels: &[[f32; 4]]) {
+        for (pixel, [r, g, b, a]) in self.buffer.iter_mut().zip(pixels) {
+            *pixel = Color::from_linear(*r, *g, *b, *a).0;
+        }
+    }
+
+    fn draw_glyph(&mut self, x: i32, y: i32, glyph: [u8; 8], raw_color: u32) {
+        for (row, bits) in (0..).zip(glyph) {
+            for col in 0..8 {
+                if bits & (0x80 >> col) != 0 {
+                    self.set_pixel(x.saturating_add(col), y.saturating_add(row), raw_color);
+                }
+            }
+        }
+    }
+
+    // Draws a glyph whose top left corner is at (u, v) in text space, which is rotated around (x, y).
+    // Every destination pixel center in the rotated bounding box is mapped back into the glyph, so there are no holes.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::similar_names
+    )]
+    fn draw_glyph_rotated(
+        &mut self,
+        x: i32,
+        y: i32,
+        (u, v): (i32, i32),
+        (sin, cos): (f64, f64),
+        glyph: [u8; 8],
+        raw_color: u32,
+    ) {
+        let (u, v) = (f64::from(u), f64::from(v));
+        let size = f64::from(font::GLYPH_WIDTH);
+        let to_screen = |gu: f64, gv: f64| (gu * cos - gv * sin, gu * sin + gv * cos);
+
+        let corners = [
+            to_screen(u, v),
+            to_screen(u + size, v),
+            to_screen(u, v + size),
+            to_screen(u + size, v + size),
+        ];
+        let min_x = corners.iter().map(|c| c.0).fold(f64::INFINITY, f64::min);
+        let max_x = corners
+            .iter()
+            .map(|c| c.0)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let min_y = corners.iter().map(|c| c.1).fold(f64::INFINITY, f64::min);
+        let max_y = corners
+            .iter()
+            .map(|c| c.1)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let (from_x, to_x, from_y, to_y) = self.clamp_rect_i32(
+            x.saturating_add(min_x.floor() as i32),
+            x.saturating_add(max_x.ceil() as i32),
+            y.saturating_add(min_y.floor() as i32),
+            y.saturating_add(max_y.ceil() as i32),
+        );
+
+        for py in from_y..to_y {
+            let dy = f64::from(py - y) + 0.5;
+            for px in from_x..to_x {
+                let dx = f64::from(px - x) + 0.5;
+                let col = (dx * cos + dy * sin - u).floor();
+                let row = (-dx * sin + dy * cos - v).floor();
+
+                if (0.0..size).contains(&col)
+                    && (0.0..size).contains(&row)
+                    && glyph[row as usize] & (0x80 >> col as u32) != 0
+                {
+                    // SAFETY: px and py are clamped to the canvas.
+                    unsafe {
+                        self.set_pixel_unchecked_raw_i32(px, py, raw_color);
+                    }
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    #[inline]
+    unsafe fn set_pixel_unchecked_raw_i32(&mut self, x: i32, y: i32, raw_color: u32) {
+        debug_assert!(x >= 0 && y >= 0);
+        let idx = y as usize * self.width + x as usize;
+
+        debug_assert!(idx < self.buffer.len());
+        let pixel = self.buffer.get_unchecked_mut(idx);
+        *pixel = combine(self.raster_op, self.blend_mode, *pixel, raw_color);
+    }
+
+    // Walks the rows of a filled circle, calling `span` with the clipped buffer range of each row exactly once.
+    #[allow(clippy::cast_sign_loss, clippy::many_single_char_names)]
+    fn fill_circle_spans(
+        &mut self,
+        x: i32,
+        y: i32,
+        mut r: i32,
+        mut span: impl FnMut(&mut Self, Range<usize>),
+    ) {
+        if r < 1 {
+            return;
+        }
+
+        let mut i = -r;
+        let mut j = 0;
+        let mut err = 2 - 2 * r;
+        // the first span of a row is always the widest one
+        let mut last_j = -1;
+        loop {
+            if j != last_j {
+                last_j = j;
+                //i is negative
+                let from_x = x.saturating_add(i).clamp(0, self.clamped_width);
+                let to_x = x.saturating_sub(i).clamp(from_x, self.clamped_width);
+
+                let rows = [y.saturating_sub(j), y.saturating_add(j)];
+                for &row in &rows[..if j == 0 { 1 } else { 2 }] {
+                    if 0 <= row && row < self.clamped_height {
+                        let offset = row as usize * self.width;
+                        span(self, offset + from_x as usize..offset + to_x as usize);
+                    }
+                }
+            }
+
+            r = err;
+            if r <= j {
+                j += 1;
+                err += j * 2 + 1;
+            }
+            if r > i || err > j {
+                i += 1;
+                err += i * 2 + 1;
+            }
+
+            if i >= 0 {
+                break;
+            }
+        }
+    }
+
+    // Draws the pixels of a circle centered at (x, y) whose distance from the center is between `inner` and `outer`,
+    // blending `color` weighted by `coverage(distance)`. With `fill_inside` the pixels within `inner` are filled as well.
+    #[allow(
+        clippy::too_many_arguments,
+        clippy::many_single_char_names,
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn circle_aa(
+        &mut self,
+        x: i32,
+        y: i32,
+        inner: f64,
+        outer: f64,
+        fill_inside: bool,
+        color: Color,
+        coverage: impl Fn(f64) -> f64,
+    ) {
+        let raw_color = u32::from(color);
+        let (r, g, b, a) = PixelFormat::unpack(raw_color);
+        let (cx, cy) = (i64::from(x), i64::from(y));
+        let reach = outer.ceil() as i64;
+        let (width, height) = (
+            i64::from(self.clamped_width),
+            i64::from(self.clamped_height),
+        );
+
+        for row in (cy - reach).max(0)..(cy + reach + 1).min(height) {
+            let dy = (row - cy) as f64;
+            let outer_w = (outer * outer - dy * dy).max(0.0).sqrt().floor() as i64;
+            // the half width of the pixels within `inner`, -1 if there are none
+            let inner_w = if inner > 0.0 && inner * inner >= dy * dy {
+                (inner * inner - dy * dy).sqrt().floor() as i64
+            } else {
+                -1
+            };
+
+            if fill_inside && inner_w >= 0 {
+                let from = (cx - inner_w).clamp(0, width);
+                let to = (cx + inner_w + 1).clamp(from, width);
+                let offset = row as usize * self.width;
+                self.fill_span_raw(offset + from as usize..offset + to as usize, raw_color);
+            }
+
+            // a single range if there are no pixels within `inner`, so the center column is drawn once
+            let edges = if inner_w < 0 {
+                [cx - outer_w..cx + outer_w + 1, 0..0]
+            } else {
+                [
+                    cx - outer_w..cx - inner_w,
+                    cx + inner_w + 1..cx + outer_w + 1,
+                ]
+            };
+            for edge in &edges {
+                for column in edge.start.max(0)..edge.end.min(width) {
+                    let dx = (column - cx) as f64;
+                    let weight = coverage((dx * dx + dy * dy).sqrt()).min(1.0);
+                    if weight > 0.0 {
+                        let index = row as usize * self.width + column as usize;
+                        let faded =
+                            PixelFormat::pack(r, g, b, (f64::from(a) * weight).round() as u8);
+                        let pixel = &mut self.buffer[index];
+                        *pixel = combine(self.raster_op, BlendMode::Alpha, *pixel, faded);
+                    }
+                }
+            }
+        }
+    }
+
+    // Walks the pixels of a line from (x1, y1) to (x2, y2) with Bresenham's algorithm,
+    // calling `pixel` with the coordinates (which may be outside this canvas) and the index of every step.
+    fn walk_line(
+        &mut self,
+        mut x1: i32,
+        mut y1: i32,
+        x2: i32,
+        y2: i32,
+        mut pixel: impl FnMut(&mut Self, i32, i32, u64),
+    ) {
+        let dx = (x2 - x1).abs();
+        let sx = if x1 < x2 { 1 } else { -1 };
+
+        let dy = -(y2 - y1).abs();
+        let sy = if y1 < y2 { 1 } else { -1 };
+
+        let mut err = dx + dy;
+
+        let mut step = 0;
+        loop {
+            pixel(self, x1, y1, step);
+
+            if x1 == x2 && y1 == y2 {
+                break;
+            }
+
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x1 += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y1 += sy;
+            }
+            step += 1;
+        }
+    }
+
+    // Walks the offsets (from the center) of the pixels of a circle outline, calling `point` with each of them exactly once.
+    fn outline_circle_offsets(&mut self, mut r: i32, mut point: impl FnMut(&mut Self, i32, i32)) {
+        let mut i = -r;
+        let mut j = 0;
+        let mut err = 2 - 2 * r;
+        loop {
+            // (-i, j) walks the quadrant where dx > 0 and dy >= 0, rotating it by 90° covers the rest exactly once
+            for &(dx, dy) in &[(-i, j), (-j, -i), (i, -j), (j, i)] {
+                point(self, dx, dy);
+            }
+
+            r = err;
+            if r <= j {
+                j += 1;
+                err += j * 2 + 1;
+            }
+            if r > i || err > j {
+                i += 1;
+                err += i * 2 + 1;
+            }
+
+            if i >= 0 {
+                break;
+            }
+        }
+    }
+
+    #[inline]
+    fn fill_span_raw(&mut self, range: Range<usize>, raw_color: u32) {
+        let (raster_op, blend_mode) = (self.raster_op, self.blend_mode);
+        let opaque = blend_mode == BlendMode::Replace || PixelFormat::unpack(raw_color).3 == 255;
+
+        if raster_op == RasterOp::Copy && opaque {
+            self.buffer[range].fill(raw_color);
+        } else {
+            for pixel in &mut self.buffer[range] {
+                *pixel = combine(raster_op, blend_mode, *pixel, raw_color);
+            }
+        }
+    }
+}
+
+// Converts a list of points to (x, y) tuples.
+fn point_tuples<P: Into<Point> + Copy>(points: &[P]) -> Vec<(i32, i32)> {
+    points.iter().map(|&p| p.into().into()).collect()
+}
+
+// The vertices of a regular polygon, rounded to the nearest pixel. None if there is nothing to draw.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn regular_polygon_vertices(
+    x: i32,
+    y: i32,
+    r: i32,
+    sides: u32,
+    rotation: f32,
+) -> Option<Vec<(i32, i32)>> {
+    if sides < 3 || r < 1 {
+        return None;
+    }
+
+    let step = std::f64::consts::TAU / f64::from(sides);
+    Some(
+        (0..sides)
+            .map(|i| {
+                let (sin, cos) = (f64::from(rotation) + step * f64::from(i)).sin_cos();
+                let vx = f64::from(x) + f64::from(r) * cos;
+                let vy = f64::from(y) + f64::from(r) * sin;
+                (vx.round() as i32, vy.round() as i32)
+            })
+            .collect(),
+    )
+}
+
+// The greatest distance (in pixels) a flattened Bézier curve may deviate from the real one.
+const BEZIER_TOLERANCE: f64 = 0.25;
+// Bounds the subdivision, so pathological control points can not hang. 2^16 segments are plenty for any canvas.
+const BEZIER_MAX_DEPTH: u32 = 16;
+
+// A quadratic Bézier curve is exactly representable as a cubic one.
+fn flatten_quadratic_bezier(p0: (i32, i32), c: (i32, i32), p1: (i32, i32)) -> Vec<(i32, i32)> {
+    let (x0, y0) = (f64::from(p0.0), f64::from(p0.1));
+    let (cx, cy) = (f64::from(c.0), f64::from(c.1));
+    let (x1, y1) = (f64::from(p1.0), f64::from(p1.1));
+    flatten_cubic_bezier([
+        x0,
+        y0,
+        x0 + 2.0 / 3.0 * (cx - x0),
+        y0 + 2.0 / 3.0 * (cy - y0),
+        x1 + 2.0 / 3.0 * (cx - x1),
+        y1 + 2.0 / 3.0 * (cy - y1),
+        x1,
+        y1,
+    ])
+}
+
+// Approximates a cubic Bézier curve ([x0, y0, c1x, c1y, c2x, c2y, x1, y1]) with a polyline,
+// rounded to the nearest pixel. Consecutive duplicate points are dropped, but there are always at least 2 points.
+#[allow(clippy::cast_possible_truncation)]
+fn flatten_cubic_bezier(curve: [f64; 8]) -> Vec<(i32, i32)> {
+    fn subdivide(c: [f64; 8], depth: u32, points: &mut Vec<(i32, i32)>) {
+        // An upper bound of the distance between the curve and its chord (squared and multiplied by 16).
+        let ux = (3.0 * c[2] - 2.0 * c[0] - c[6]).powi(2);
+        let uy = (3.0 * c[3] - 2.0 * c[1] - c[7]).powi(2);
+        let vx = (3.0 * c[4] - c[0] - 2.0 * c[6]).powi(2);
+        let vy = (3.0 * c[5] - c[1] - 2.0 * c[7]).powi(2);
+
+        let flat = ux.max(vx) + uy.max(vy) <= 16.0 * BEZIER_TOLERANCE * BEZIER_TOLERANCE;
+        // NaN (from non finite coordinates) can never be subdivided into something flat
+        if depth == 0 || flat || ux.is_nan() || uy.is_nan() || vx.is_nan() || vy.is_nan() {
+            let point = (c[6].round() as i32, c[7].round() as i32);
+            if points.last() != Some(&point) {
+                points.push(point);
+            }
+            return;
+        }
+
+        // de Casteljau's algorithm at t = 0.5
+        let mid = |a: f64, b: f64| (a + b) * 0.5;
+        let (ax, ay) = (mid(c[0], c[2]), mid(c[1], c[3]));
+        let (bx, by) = (mid(c[2], c[4]), mid(c[3], c[5]));
+        let (cx, cy) = (mid(c[4], c[6]), mid(c[5], c[7]));
+        let (abx, aby) = (mid(ax, bx), mid(ay, by));
+        let (bcx, bcy) = (mid(bx, cx), mid(by, cy));
+        let (mx, my) = (mid(abx, bcx), mid(aby, bcy));
+
+        subdivide([c[0], c[1], ax, ay, abx, aby, mx, my], depth - 1, points);
+        subdivide([mx, my, bcx, bcy, cx, cy, c[6], c[7]], depth - 1, points);
+    }
+
+    let start = (curve[0].round() as i32, curve[1].round() as i32);
+    let mut points = vec![start];
+    subdivide(curve, BEZIER_MAX_DEPTH, &mut points);
+
+    // a curve collapsing into a single pixel is still drawn
+    if points.len() == 1 {
+        points.push(start);
+    }
+    points
+}
+
+// A small, fast and well distributed pseudo random number generator, good enough for procedural patterns.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// Classic 2D Perlin noise with a seeded permutation table.
+struct PerlinNoise {
+    permutation: [u8; 512],
+}
+
+impl PerlinNoise {
+    #[allow(clippy::cast_possible_truncation)]
+    fn new(seed: u64) -> Self {
+        let mut table = [0u8; 256];
+        for (i, value) in (0u8..=255).zip(table.iter_mut()) {
+            *value = i;
+        }
+
+        // Fisher-Yates shuffle
+        let mut state = seed;
+        for i in (1..table.len()).rev() {
+            let j = (splitmix64(&mut state) % (i as u64 + 1)) as usize;
+            table.swap(i, j);
+        }
+
+        let mut permutation = [0u8; 512];
+        for (i, value) in permutation.iter_mut().enumerate() {
+            *value = table[i % 256];
+        }
+        Self { permutation }
+    }
+
+    // Returns the noise at (x, y), roughly in -1.0..=1.0.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn sample(&self, x: f32, y: f32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (xi, yi) = (
+            (x0 as i64).rem_euclid(256) as usize,
+            (y0 as i64).rem_euclid(256) as usize,
+        );
+
+        let perm = &self.permutation;
+        let hash = |dx: usize, dy: usize| perm[perm[xi + dx] as usize + yi + dy];
+        let corner = |dx: usize, dy: usize| {
+            let (gx, gy) = (fx - dx as f32, fy - dy as f32);
+            match hash(dx, dy) & 7 {
+                0 => gx + gy,
+                1 => gx - gy,
+                2 => -gx + gy,
+                3 => -gx - gy,
+                4 => gx,
+                5 => -gx,
+                6 => gy,
+                _ => -gy,
+            }
+        };
+
+        let fade = |t: f32| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        let (fade_x, fade_y) = (fade(fx), fade(fy));
+
+        lerp(
+            lerp(corner(0, 0), corner(1, 0), fade_x),
+            lerp(corner(0, 1), corner(1, 1), fade_x),
+            fade_y,
+        )
+    }
+}
+
+// A rectangle to be copied between two canvases, already clipped to both.
+struct Blit {
+    // top left corners
+    src: (usize, usize),
+    dst: (usize, usize),
+    width: usize,
+    height: usize,
+}
+
+impl Blit {
+    // The buffer indices of the first pixel of the given row in the source and the destination.
+    fn row_starts(&self, row: usize, src_width: usize, dst_width: usize) -> (usize, usize) {
+        (
+            (self.src.1 + row) * src_width + self.src.0,
+            (self.dst.1 + row) * dst_width + self.dst.0,
+        )
+    }
+}
+
+// Whether every channel (alpha included) of the two raw colors differs by at most `tolerance`.
+fn within_tolerance(a: u32, b: u32, tolerance: u8) -> bool {
+    a == b
+        || a.to_le_bytes()
+            .iter()
+            .zip(b.to_le_bytes().iter())
+            .all(|(a, b)| (i16::from(*a) - i16::from(*b)).abs() <= i16::from(tolerance))
+}
+
+// Box blurs `lines` lines of `len` pixels each in place. Consecutive lines start `line_stride` apart
+// and consecutive pixels of a line are `step` apart: rows have a stride of width and step 1, columns the other way around.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn box_blur_lines(
+    pixels: &mut [[f32; 4]],
+    lines: usize,
+    line_stride: usize,
+    len: usize,
+    step: usize,
+    radius: u32,
+) {
+    let radius = i64::from(radius);
+    let last = len as i64 - 1;
+    let window = (2 * radius + 1) as f64;
+    let mut line = vec![[0.0f32; 4]; len];
+
+    for l in 0..lines {
+        let start = l * line_stride;
+        for (i, value) in line.iter_mut().enumerate() {
+            *value = pixels[start + i * step];
+        }
+
+        // the window of the first pixel: radius + 1 copies of the first pixel, then the rest clamped to the last pixel
+        let mut sum = [0.0f64; 4];
+        add_pixel(&mut sum, line[0], radius as f64);
+        for k in 0..=radius.min(last) {
+            add_pixel(&mut sum, line[k as usize], 1.0);
+        }
+        if radius > last {
+            add_pixel(&mut sum, line[last as usize], (radius - last) as f64);
+        }
+
+        for i in 0..len {
+            if i > 0 {
+                let i = i as i64;
+                add_pixel(&mut sum, line[(i + radius).min(last) as usize], 1.0);
+                add_pixel(&mut sum, line[(i - radius - 1).max(0) as usize], -1.0);
+            }
+
+            let pixel = &mut pixels[start + i * step];
+            for (channel, total) in pixel.iter_mut().zip(sum.iter()) {
+                *channel = (total / window) as f32;
+            }
+        }
+    }
+}
+
+// For every destination pixel of a 1D area resampling from src_len to dst_len (<= src_len) pixels,
+// the source pixels it covers together with their share of its area.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn box_weights(src_len: usize, dst_len: usize) -> Vec<Vec<(usize, f64)>> {
+    let scale = src_len as f64 / dst_len as f64;
+    (0..dst_len)
+        .map(|i| {
+            let (start, end) = (
+                i as f64 * scale,
+                ((i + 1) as f64 * scale).min(src_len as f64),
+            );
+            (start.floor() as usize..end.ceil() as usize)
+                .map(|j| {
+                    let overlap = end.min(j as f64 + 1.0) - start.max(j as f64);
+                    (j, overlap / (end - start))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn weighted_sum(pixels: impl Iterator<Item = ([f32; 4], f64)>) -> [f32; 4] {
+    let mut sum = [0.0f64; 4];
+    for (pixel, weight) in pixels {
+        add_pixel(&mut sum, pixel, weight);
+    }
+    sum.map(|channel| channel as f32)
+}
+
+fn add_pixel(sum: &mut [f64; 4], pixel: [f32; 4], weight: f64) {
+    for (total, channel) in sum.iter_mut().zip(pixel.iter()) {
+        *total += f64::from(*channel) * weight;
+    }
+}
+
+/// The result of comparing two canvases with [`Canvas::diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStats {
+    /// The canvases have different dimensions, so their pixels were not compared.
+    DimensionMismatch,
+    /// `count` pixels differ, all of them within `bounds`, given as (x, y, w, h).
+    Pixels {
+        count: usize,
+        bounds: (usize, usize, usize, usize),
+    },
+}
+
+/// How [`Canvas`] combines the color being drawn with the pixel already there, see [`Canvas::set_raster_op`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RasterOp {
+    /// The pixel is overwritten with the color. This is the default.
+    Copy,
+    /// The pixel is combined with the color by bitwise XOR, alpha included.
+    /// Drawing the same shape twice restores the original pixels.
+    Xor,
+}
+
+impl Default for RasterOp {
+    fn default() -> Self {
+        Self::Copy
+    }
+}
+
+/// Whether [`Canvas`] takes the alpha of the color being drawn into account, see [`Canvas::set_blend_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    /// The color replaces the pixel, alpha included. This is the default, and the fastest.
+    Replace,
+    /// The color is composited over the pixel according to its alpha ("source over").
+    /// An alpha of 255 replaces the pixel, an alpha of 0 leaves it untouched.
+    Alpha,
+}
+
+impl Default for BlendMode {
+    fn default() -> Self {
+        Self::Replace
+    }
+}
+
+// The pixel resulting from drawing `raw_color` over `pixel`.
+#[inline]
+fn combine(raster_op: RasterOp, blend_mode: BlendMode, pixel: u32, raw_color: u32) -> u32 {
+    match (raster_op, blend_mode) {
+        (RasterOp::Xor, _) => pixel ^ raw_color,
+        (RasterOp::Copy, BlendMode::Replace) => raw_color,
+        (RasterOp::Copy, BlendMode::Alpha) => Color(raw_color).blend_over(Color(pixel)).0,
+    }
+}
+
+// The pixels a span fill operates on.
+struct FillTarget<'a, F> {
+    buffer: &'a mut [u32],
+    // empty if visited pixels are not tracked
+    visited: Vec<bool>,
+    raw_color: u32,
+    inside: F,
+    filled: usize,
+}
+
+impl<F: Fn(u32) -> bool> FillTarget<'_, F> {
+    #[inline]
+    fn is_inside(&self, idx: usize) -> bool {
+        (self.visited.is_empty() || !self.visited[idx]) && (self.inside)(self.buffer[idx])
+    }
+
+    #[inline]
+    fn set(&mut self, idx: usize) {
+        self.buffer[idx] = self.raw_color;
+        self.filled += 1;
+        if !self.visited.is_empty() {
+            self.visited[idx] = true;
+        }
     }
+}
 
-    /// Renders a vertical line with thickness. Should be preferred when explicitly drawing thick vertical lines.
-    /// ``` rust
-    /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32; 256];
-    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.thick_vline(10, 0, 16, 2, Color::RED);
-    #[inline]
-    pub fn thick_vline(
-        &mut self,
-        x: i32,
-        y1: i32,
-        y2: i32,
-        thickness: i32,
-        color: impl Into<Color>,
-    ) {
-        let thickness = thickness.max(0);
-        let (y1, y2) = if y1 > y2 { (y2, y1) } else { (y1, y2) };
-        self.fill_rect(x - thickness / 2, y1, thickness, y2 - y1, color);
+// The angles (in radians, clockwise from the positive x axis) swept by an arc, from `start` towards increasing angles.
+struct Sweep {
+    start: f64,
+    length: f64,
+    full: bool,
+}
+
+impl Sweep {
+    fn new(start: f32, end: f32) -> Self {
+        let (start, end) = (f64::from(start), f64::from(end));
+        Self {
+            start: start.rem_euclid(std::f64::consts::TAU),
+            length: (end - start).rem_euclid(std::f64::consts::TAU),
+            full: (end - start).abs() >= std::f64::consts::TAU,
+        }
     }
 
-    /// Renders a line. Should be preferred when mostly drawing non axis-aligned lines.
-    /// If there is a substantial chance of drawing axis-aligned (hline or vline) consider using [`line_maybe_axis_aligned`](struct.Canvas.html#method.line_maybe_axis_aligned) instead
-    /// ``` rust
-    /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32; 256];
-    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.line(10, 2, 10, 12, Color::RED);
-    /// ```
-    pub fn line(&mut self, mut x1: i32, mut y1: i32, x2: i32, y2: i32, color: impl Into<Color>) {
-        let raw_color = u32::from(color.into());
+    // Whether the direction of the offset (dx, dy) is inside the sweep. The center itself is always inside.
+    #[allow(clippy::float_cmp)]
+    fn contains(&self, dx: f64, dy: f64) -> bool {
+        if self.full || (dx == 0.0 && dy == 0.0) {
+            return true;
+        }
+        (dy.atan2(dx) - self.start).rem_euclid(std::f64::consts::TAU) <= self.length
+    }
 
-        let dx = (x2 - x1).abs();
-        let sx = if x1 < x2 { 1 } else { -1 };
+    // Whether the pixel at offset (dx, dy) lies on one of the two radii bounding the sweep.
+    fn on_radius(&self, dx: f64, dy: f64) -> bool {
+        [self.start, self.start + self.length].iter().any(|angle| {
+            let (sin, cos) = angle.sin_cos();
+            dx * cos + dy * sin >= 0.0 && (dy * cos - dx * sin).abs() <= 0.5
+        })
+    }
+}
 
-        let dy = -(y2 - y1).abs();
-        let sy = if y1 < y2 { 1 } else { -1 };
+// Edge function of a triangle edge going from (x1, y1) to (x2, y2), evaluated at integer pixel coordinates:
+// e(x, y) = (x2 - x1) * (y - y1) - (y2 - y1) * (x - x1) = a * x + b(y)
+struct Edge {
+    x1: i128,
+    y1: i128,
+    dx: i128,
+    dy: i128,
+    // minimal value of the edge function for a pixel to be considered inside (top-left rule).
+    bias: i128,
+}
 
-        let mut err = dx + dy;
+// The products of coordinate differences do not fit into an i64 at the extremes of i32, hence the i128s.
+impl Edge {
+    fn new(x1: i32, y1: i32, x2: i32, y2: i32) -> Self {
+        let dx = i128::from(x2) - i128::from(x1);
+        let dy = i128::from(y2) - i128::from(y1);
+        // with y pointing downwards, the inside is to the right of a left edge (a > 0)
+        // and below a horizontal top edge.
+        let is_top_left = dy < 0 || (dy == 0 && dx > 0);
+        Self {
+            x1: i128::from(x1),
+            y1: i128::from(y1),
+            dx,
+            dy,
+            bias: i128::from(!is_top_left),
+        }
+    }
 
-        loop {
-            if 0 <= x1 && x1 < self.clamped_width && 0 <= y1 && y1 < self.clamped_height {
-                unsafe {
-                    self.set_pixel_unchecked_raw_i32(x1, y1, raw_color);
+    // Returns the (inclusive) range of x in row y where the edge function is at least bias.
+    fn span(&self, y: i32) -> (i64, i64) {
+        let a = -self.dy;
+        let b = self.dx * (i128::from(y) - self.y1) + self.dy * self.x1;
+        // a * x + b >= bias
+        let rhs = self.bias - b;
+        let (min, max) = match a.cmp(&0) {
+            std::cmp::Ordering::Greater => (-(-rhs).div_euclid(a), i128::MAX),
+            std::cmp::Ordering::Less => (i128::MIN, (-rhs).div_euclid(-a)),
+            std::cmp::Ordering::Equal if b >= self.bias => (i128::MIN, i128::MAX),
+            std::cmp::Ordering::Equal => (i128::MAX, i128::MIN),
+        };
+        let saturate =
+            |v: i128| i64::try_from(v).unwrap_or(if v < 0 { i64::MIN } else { i64::MAX });
+        (saturate(min), saturate(max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Naive 4-connected reference fill.
+    fn reference_fill(
+        buffer: &mut [u32],
+        width: usize,
+        (x, y): (usize, usize),
+        raw_color: u32,
+        diagonal: bool,
+    ) {
+        let height = buffer.len() / width;
+        let seed_color = buffer[y * width + x];
+        if seed_color == raw_color {
+            return;
+        }
+        let mut stack = vec![(x, y)];
+        while let Some((x, y)) = stack.pop() {
+            if buffer[y * width + x] != seed_color {
+                continue;
+            }
+            buffer[y * width + x] = raw_color;
+            for dy in -1i64..=1 {
+                for dx in -1i64..=1 {
+                    if (dx == 0 && dy == 0) || (!diagonal && dx != 0 && dy != 0) {
+                        continue;
+                    }
+                    let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+                    if 0 <= nx && nx < width as i64 && 0 <= ny && ny < height as i64 {
+                        stack.push((nx as usize, ny as usize));
+                    }
                 }
             }
+        }
+    }
 
-            if x1 == x2 && y1 == y2 {
-                break;
-            }
+    #[test]
+    fn content_hash_is_stable() {
+        let mut buffer = [0xffff_0000, 0, 0, 0xff00_ff00];
+        let canvas = Canvas::new(&mut buffer, 2, 2);
+        assert_eq!(canvas.content_hash(), 0x6bc9_cff7_70ac_0f55);
 
-            let e2 = 2 * err;
-            if e2 >= dy {
-                err += dy;
-                x1 += sx;
+        let mut transposed = [0xffff_0000, 0, 0, 0xff00_ff00];
+        let transposed = Canvas::new(&mut transposed, 4, 1);
+        assert_ne!(canvas.content_hash(), transposed.content_hash());
+    }
+
+    #[test]
+    fn flood_fill_large_area() {
+        let mut buffer = vec![0u32; 2000 * 2000];
+        let mut canvas = Canvas::new(&mut buffer, 2000, 2000);
+        canvas.flood_fill(1000, 1000, Color::RED);
+        assert!(buffer.iter().all(|&p| p == u32::from(Color::RED)));
+    }
+
+    #[test]
+    fn flood_fill_matches_reference() {
+        let (w, h) = (64, 48);
+        let mut buffer = vec![0u32; w * h];
+        let mut canvas = Canvas::new(&mut buffer, w, h);
+        canvas.outline_circle(20, 20, 15, Color::WHITE);
+        canvas.outline_rect(30, 5, 30, 40, Color::WHITE);
+        canvas.line(0, 47, 63, 0, Color::WHITE);
+        canvas.fill_triangle(40, 10, 55, 12, 45, 40, Color::BLUE);
+        // serpentine walls
+        for i in 0..6 {
+            let x = 33 + i * 4;
+            if i % 2 == 0 {
+                canvas.vline(x, 6, 38, Color::WHITE);
+            } else {
+                canvas.vline(x, 12, 44, Color::WHITE);
             }
-            if e2 <= dx {
-                err += dx;
-                y1 += sy;
+        }
+
+        let original = buffer.clone();
+        let seeds = [(20, 20), (2, 2), (35, 30), (62, 46), (45, 20), (30, 5)];
+        for &diagonal in &[false, true] {
+            for &(x, y) in &seeds {
+                let mut expected = buffer.clone();
+                reference_fill(&mut expected, w, (x, y), u32::from(Color::GREEN), diagonal);
+                let expected_count = expected
+                    .iter()
+                    .zip(buffer.iter())
+                    .filter(|(a, b)| a != b)
+                    .count();
+
+                let mut canvas = Canvas::new(&mut buffer, w, h);
+                let count = if diagonal {
+                    canvas.flood_fill_8(x as i32, y as i32, Color::GREEN)
+                } else {
+                    canvas.flood_fill(x as i32, y as i32, Color::GREEN)
+                };
+                assert_eq!(
+                    buffer, expected,
+                    "fill from ({}, {}), diagonal: {}",
+                    x, y, diagonal
+                );
+                assert_eq!(count, expected_count, "count from ({}, {})", x, y);
+
+                // reset for the next seed
+                buffer.copy_from_slice(&original);
             }
         }
     }
 
-    /// Renders a line. Should be preferred when mostly drawing axis-aligned lines.
-    /// If it is not very likely you'll draw a lot of axis-aligned lines prefer [`line`](struct.Canvas.html#method.line) instead.
-    /// ``` rust
-    /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32; 256];
-    /// let mut canvas = Canvas::new(&mut buffer, 16,16);
-    /// // axis aligned
-    /// canvas.line_maybe_axis_aligned(10, 2, 10, 12, Color::RED);
-    /// // not axis aligned
-    /// canvas.line_maybe_axis_aligned(12, 4, 5, 7, Color::BLUE);
-    /// ```
-    #[inline]
-    pub fn line_maybe_axis_aligned(
-        &mut self,
-        x1: i32,
-        y1: i32,
-        x2: i32,
-        y2: i32,
-        color: impl Into<Color>,
-    ) {
-        if x1 == x2 {
-            self.vline(x1, y1, y2, color);
-        } else if y1 == y2 {
-            self.hline(y1, x1, x2, color);
+    // Measures the stroke width of a line perpendicular to its direction,
+    // by counting the covered pixels across the middle of the line.
+    #[allow(clippy::cast_precision_loss)]
+    fn measure_thick_line(angle_deg: f64, thickness: i32) -> f64 {
+        const SIZE: usize = 128;
+        let mut buffer = vec![0u32; SIZE * SIZE];
+        let mut canvas = Canvas::new(&mut buffer, SIZE, SIZE);
+
+        let (dy, dx) = angle_deg.to_radians().sin_cos();
+        let (x1, y1) = (
+            (64.0 - dx * 40.0).round() as i32,
+            (64.0 - dy * 40.0).round() as i32,
+        );
+        let (x2, y2) = (
+            (64.0 + dx * 40.0).round() as i32,
+            (64.0 + dy * 40.0).round() as i32,
+        );
+        canvas.thick_line(x1, y1, x2, y2, thickness, Color::WHITE);
+
+        let (fdx, fdy) = (f64::from(x2 - x1), f64::from(y2 - y1));
+        let length = (fdx * fdx + fdy * fdy).sqrt();
+        let covered = |i: usize| buffer[i] != 0;
+
+        if fdx.abs() >= fdy.abs() {
+            // count along the column through the middle
+            let count = (0..SIZE).filter(|&y| covered(y * SIZE + 64)).count();
+            count as f64 * fdx.abs() / length
         } else {
-            self.line(x1, y1, x2, y2, color);
+            // count along the row through the middle
+            let count = (0..SIZE).filter(|&x| covered(64 * SIZE + x)).count();
+            count as f64 * fdy.abs() / length
         }
     }
 
-    /// Renders a line with thickness. Should be preferred when mostly drawing non axis-aligned lines.
-    /// If there is a substantial chance of drawing axis-aligned (hline or vline) consider using [`thick_line_maybe_axis_aligned`](struct.Canvas.html#method.thick_line_maybe_axis_aligned) instead
-    /// ``` rust
-    /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32; 256];
-    /// let mut canvas = Canvas::new(&mut buffer, 16, 16);
-    /// canvas.thick_line(10, 2, 10, 12, 4, Color::RED);
-    /// ```
-    #[allow(clippy::similar_names, clippy::cast_possible_truncation)]
-    pub fn thick_line(
-        &mut self,
-        x1: i32,
-        y1: i32,
-        x2: i32,
-        y2: i32,
-        thickness: i32,
-        color: impl Into<Color>,
-    ) {
-        if thickness < 0 {
-            return;
-        } else if thickness == 1 {
-            self.line(x1, y1, x2, y2, color);
-            return;
+    #[test]
+    fn boundary_fill_crosses_fill_colored_pixels() {
+        let mut buffer = vec![0u32; 32 * 32];
+        let mut canvas = Canvas::new(&mut buffer, 32, 32);
+        canvas.outline_rect(0, 0, 32, 32, Color::WHITE);
+        // a ring that already has the fill color, enclosing a differently colored area
+        canvas.outline_rect(8, 8, 10, 10, Color::RED);
+        canvas.fill_rect(9, 9, 8, 8, Color::BLUE);
+
+        canvas.boundary_fill(3, 3, Color::RED, Color::WHITE);
+        assert!(canvas
+            .pixel_iter()
+            .all(|(x, y, p)| p == u32::from(Color::RED)
+                || ((x == 0 || x == 31 || y == 0 || y == 31) && p == u32::from(Color::WHITE))));
+
+        // seed on the border
+        canvas.boundary_fill(0, 0, Color::GREEN, Color::WHITE);
+        assert!(canvas
+            .pixel_iter()
+            .all(|(_, _, p)| p != u32::from(Color::GREEN)));
+    }
+
+    #[test]
+    fn fill_triangle_shared_edge() {
+        let triangles = [
+            // a square split along its diagonal
+            [(2, 2), (20, 2), (20, 20)],
+            [(2, 2), (20, 20), (2, 20)],
+            // a fan around (16, 16)
+            [(16, 16), (30, 3), (31, 25)],
+            [(16, 16), (31, 25), (7, 30)],
+            [(16, 16), (7, 30), (1, 9)],
+            [(16, 16), (1, 9), (30, 3)],
+        ];
+
+        for pair in [(0, 1), (2, 3), (3, 4), (4, 5), (5, 2)] {
+            let mut first = vec![0u32; 32 * 32];
+            let mut second = vec![0u32; 32 * 32];
+            for (buffer, i) in [(&mut first, pair.0), (&mut second, pair.1)] {
+                let [(x1, y1), (x2, y2), (x3, y3)] = triangles[i];
+                Canvas::new(buffer, 32, 32).fill_triangle(x1, y1, x2, y2, x3, y3, Color::WHITE);
+            }
+            assert!(
+                first.iter().zip(&second).all(|(a, b)| *a == 0 || *b == 0),
+                "triangles {:?} overlap",
+                pair
+            );
         }
 
-        let raw_color = u32::from(color.into());
+        // the split square is covered exactly, like fill_rect would do.
+        let mut buffer = vec![0u32; 32 * 32];
+        let mut canvas = Canvas::new(&mut buffer, 32, 32);
+        canvas.fill_triangle(2, 2, 20, 2, 20, 20, Color::WHITE);
+        canvas.fill_triangle(2, 2, 20, 20, 2, 20, Color::WHITE);
+        let mut expected = vec![0u32; 32 * 32];
+        Canvas::new(&mut expected, 32, 32).fill_rect(2, 2, 18, 18, Color::WHITE);
+        assert_eq!(buffer, expected);
+    }
 
-        let dx = f64::from(x2 - x1);
-        let dy = f64::from(y2 - y1);
-        let length = (dx * dx + dy * dy).sqrt();
+    #[test]
+    fn fill_triangle_extremes_and_degenerate() {
+        let (min, max) = (i32::MIN, i32::MAX);
+        let mut buffer = [0u32; 16];
+        let mut canvas = Canvas::new(&mut buffer, 4, 4);
+        canvas.fill_triangle(max, min, min, max, max, max, Color::WHITE);
+        // the whole canvas lies right below the diagonal from (max, min) to (min, max)
+        assert_eq!(buffer, [Color::WHITE.0; 16]);
+
+        // collinear vertices make a line between the outer two, in any order
+        let mut line = [0u32; 256];
+        Canvas::new(&mut line, 16, 16).line(1, 1, 14, 14, Color::WHITE);
+        for &(a, b, c) in &[((1, 1), (8, 8), (14, 14)), ((8, 8), (14, 14), (1, 1))] {
+            let mut buffer = [0u32; 256];
+            Canvas::new(&mut buffer, 16, 16).fill_triangle(
+                a.0,
+                a.1,
+                b.0,
+                b.1,
+                c.0,
+                c.1,
+                Color::WHITE,
+            );
+            assert_eq!(buffer, line);
+        }
+    }
 
-        let half_thickness = f64::from(thickness) * 0.5;
+    #[test]
+    fn thick_line_uniform_width() {
+        for thickness in 2..=9 {
+            for angle in [
+                0.0, 10.0, 22.5, 30.0, 45.0, 60.0, 75.0, 90.0, 110.0, 135.0, 170.0,
+            ] {
+                let width = measure_thick_line(angle, thickness);
+                assert!(
+                    (width - f64::from(thickness)).abs() <= 1.0,
+                    "thickness {} at {} degrees measured {}",
+                    thickness,
+                    angle,
+                    width
+                );
+            }
+        }
 
-        let px = ((-dy / length) * half_thickness) as i32;
-        let py = ((dx / length) * half_thickness) as i32;
+        // too thin for a quad, these are plain lines
+        let mut line = [0u32; 256];
+        Canvas::new(&mut line, 16, 16).line(1, 2, 14, 9, Color::WHITE);
+        for &thickness in &[0, 1] {
+            let mut buffer = [0u32; 256];
+            Canvas::new(&mut buffer, 16, 16).thick_line(1, 2, 14, 9, thickness, Color::WHITE);
+            assert_eq!(buffer, line);
+        }
+    }
 
-        let v1x = x1 + px;
-        let v1y = y1 + py;
+    #[test]
+    fn draw_text_rotated_quarter_turn_transposes() {
+        let text = "Ag{~";
+        let mut horizontal = vec![0u32; 32 * 8];
+        Canvas::new(&mut horizontal, 32, 8).draw_text(0, 0, text, Color::WHITE);
+
+        let mut vertical = vec![0u32; 8 * 32];
+        Canvas::new(&mut vertical, 8, 32).draw_text_rotated(8, 0, text, 90.0, Color::WHITE);
+
+        for row in 0..8 {
+            for col in 0..32 {
+                assert_eq!(
+                    horizontal[row * 32 + col],
+                    vertical[col * 8 + (7 - row)],
+                    "glyph pixel ({}, {})",
+                    col,
+                    row
+                );
+            }
+        }
+    }
 
-        let v2x = x1 - px;
-        let v2y = y1 - py;
+    #[test]
+    fn box_blur_matches_naive_average() {
+        let (w, h) = (7, 5);
+        let source: Vec<u32> = (0..w * h)
+            .map(|i| {
+                Color::rgb(
+                    (i * 37 % 256) as u8,
+                    (i * 91 % 256) as u8,
+                    (i * 13 % 256) as u8,
+                )
+                .0
+            })
+            .collect();
+
+        for radius in [1u32, 2, 9] {
+            let mut buffer = source.clone();
+            Canvas::new(&mut buffer, w, h).box_blur(radius);
+
+            let r = radius as i64;
+            let clamp = |v: i64, max: usize| v.max(0).min(max as i64 - 1) as usize;
+            for y in 0..h {
+                for x in 0..w {
+                    let mut sum = [0.0f64; 4];
+                    for dy in -r..=r {
+                        for dx in -r..=r {
+                            let p = source[clamp(y as i64 + dy, h) * w + clamp(x as i64 + dx, w)];
+                            let (r, g, b, a) = Color(p).to_linear();
+                            for (s, c) in sum.iter_mut().zip([r, g, b, a]) {
+                                *s += f64::from(c);
+                            }
+                        }
+                    }
+                    let n = ((2 * r + 1) * (2 * r + 1)) as f64;
+                    let [r, g, b, a] = sum.map(|s| (s / n) as f32);
+                    let expected = Color::from_linear(r, g, b, a).0;
+                    let actual = buffer[y * w + x];
+                    assert!(
+                        within_tolerance(actual, expected, 1),
+                        "radius {} at ({}, {}): {:08x} != {:08x}",
+                        radius,
+                        x,
+                        y,
+                        actual,
+                        expected
+                    );
+                }
+            }
+        }
+    }
 
-        let v3x = x2 + px;
-        let v3y = y2 + py;
+    #[test]
+    fn perlin_noise_is_smooth_and_bounded() {
+        let noise = PerlinNoise::new(1234);
+        for i in 0..2000 {
+            let (x, y) = (i as f32 * 0.173 - 150.0, i as f32 * 0.071 - 60.0);
+            let value = noise.sample(x, y);
+            assert!((-1.0..=1.0).contains(&value));
+            assert!((noise.sample(x + 0.01, y) - value).abs() < 0.05);
+            assert_eq!(noise.sample(x.floor(), y.floor()), 0.0);
+        }
+    }
 
-        let v4x = x2 - px;
-        let v4y = y2 - py;
+    #[test]
+    fn downscale_box_weights_cover_source() {
+        for &(src, dst) in &[(5, 3), (7, 7), (100, 33), (3, 1)] {
+            let weights = box_weights(src, dst);
+            let mut coverage = vec![0.0; src];
+            for pixel in &weights {
+                let total: f64 = pixel.iter().map(|(_, w)| w).sum();
+                assert!((total - 1.0).abs() < 1e-9);
+                for &(j, w) in pixel {
+                    coverage[j] += w * src as f64 / dst as f64;
+                }
+            }
+            // every source pixel contributes its whole area exactly once
+            assert!(
+                coverage.iter().all(|c| (c - 1.0).abs() < 1e-9),
+                "{} -> {}",
+                src,
+                dst
+            );
+        }
 
-        self.fill_triangle(v1x, v1y, v2x, v2y, v3x, v3y, raw_color);
-        self.fill_triangle(v2x, v2y, v4x, v4y, v3x, v3y, raw_color);
+        let mut buffer = vec![Color::TEAL.0; 5 * 7];
+        let canvas = Canvas::new(&mut buffer, 5, 7);
+        assert_eq!(canvas.downscale_box(3, 4), vec![Color::TEAL.0; 3 * 4]);
+        assert_eq!(canvas.downscale_box(9, 9).len(), 5 * 7);
     }
 
-    /// Renders a line with thickness. Should be preferred when mostly drawing axis-aligned lines.
-    /// If it is not very likely you'll draw a lot of axis-aligned lines prefer [`thick_line`](struct.Canvas.html#method.thick_line) instead.
-    /// ``` rust
-    /// use vason::{Canvas, Color};
-    /// let mut buffer = [0u32; 256];
-    /// let mut canvas = Canvas::new(&mut buffer, 16,16);
-    /// // axis aligned
-    /// canvas.thick_line_maybe_axis_aligned(10, 2, 10, 12, 3, Color::RED);
-    /// // not axis aligned
-    /// canvas.thick_line_maybe_axis_aligned(12, 4, 5, 7, 4, Color::BLUE);
-    /// ```
-    #[inline]
-    pub fn thick_line_maybe_axis_aligned(
-        &mut self,
-        x1: i32,
-        y1: i32,
-        x2: i32,
-        y2: i32,
-        thickness: i32,
-        color: impl Into<Color>,
-    ) {
-        if x1 == x2 {
-            self.thick_vline(x1, y1, y2, thickness, color);
-        } else if y1 == y2 {
-            self.thick_hline(y1, x1, x2, thickness, color);
-        } else {
-            self.thick_line(x1, y1, x2, y2, thickness, color);
+    #[test]
+    fn blit_region_clips_both_sides() {
+        let mut source = (0..25u32).collect::<Vec<_>>();
+        let source = Canvas::new(&mut source, 5, 5);
+
+        for &(rect, dx, dy) in &[
+            ((-2, -1, 4, 4), -1, 2),
+            ((1, 1, 10, 10), 3, -2),
+            ((0, 0, 5, 5), 0, 0),
+            ((3, 2, 2, 3), 4, 4),
+            ((2, 2, 0, 3), 1, 1),
+            ((-10, 0, 5, 5), 0, 0),
+        ] {
+            let mut buffer = vec![u32::MAX; 6 * 4];
+            Canvas::new(&mut buffer, 6, 4).blit_region(&source, rect, dx, dy);
+
+            let (rx, ry, rw, rh) = rect;
+            for y in 0..4i32 {
+                for x in 0..6i32 {
+                    let (sx, sy) = (x - dx + rx, y - dy + ry);
+                    let inside_rect = rx <= sx && sx < rx + rw && ry <= sy && sy < ry + rh;
+                    let inside_src = (0..5).contains(&sx) && (0..5).contains(&sy);
+                    let expected = if inside_rect && inside_src {
+                        (sy * 5 + sx) as u32
+                    } else {
+                        u32::MAX
+                    };
+                    assert_eq!(
+                        buffer[(y * 6 + x) as usize],
+                        expected,
+                        "{:?} at ({}, {})",
+                        rect,
+                        x,
+                        y
+                    );
+                }
+            }
         }
     }
 
-    /// Starts a flood fill from supplied coordinate filling the area with the color provided.
-    #[allow(clippy::cast_sign_loss)]
-    pub fn flood_fill(&mut self, x: i32, y: i32, color: impl Into<Color>) {
-        if 0 <= x && x < self.clamped_width && 0 <= y && y < self.clamped_height {
-            let raw_color = u32::from(color.into());
-            let xu = x as usize;
-            let yu = y as usize;
-            let seed_color = self.buffer[yu * self.width + xu];
-            if seed_color != raw_color {
-                self.flood_fill_start(xu, yu, seed_color, raw_color);
+    #[test]
+    fn blit_masked_clips_and_aligns_mask() {
+        let mut source = (0..25u32).collect::<Vec<_>>();
+        let source = Canvas::new(&mut source, 5, 5);
+        // some pixels of every row and column are masked out, so a shifted mask shows
+        let mask: Vec<u8> = (0..25)
+            .map(|i| if (i % 5 * 3 + i / 5) % 4 == 0 { 0 } else { 255 })
+            .collect();
+
+        for &(dx, dy) in &[
+            (-2, -1),
+            (3, 2),
+            (-4, -4),
+            (4, 3),
+            (0, 0),
+            (i32::MIN, 0),
+            (0, i32::MAX),
+            (i32::MAX, i32::MIN),
+        ] {
+            let mut buffer = vec![u32::MAX; 6 * 4];
+            Canvas::new(&mut buffer, 6, 4).blit_masked(&source, &mask, dx, dy);
+
+            for y in 0..4i64 {
+                for x in 0..6i64 {
+                    let (sx, sy) = (x - i64::from(dx), y - i64::from(dy));
+                    let inside_src = (0..5).contains(&sx) && (0..5).contains(&sy);
+                    let expected = if inside_src && mask[(sy * 5 + sx) as usize] == 255 {
+                        (sy * 5 + sx) as u32
+                    } else {
+                        u32::MAX
+                    };
+                    assert_eq!(
+                        buffer[(y * 6 + x) as usize],
+                        expected,
+                        "({}, {}) at ({}, {})",
+                        dx,
+                        dy,
+                        x,
+                        y
+                    );
+                }
             }
         }
     }
 
-    fn flood_fill_start(&mut self, mut x: usize, mut y: usize, seed_color: u32, raw_color: u32) {
-        loop {
-            let ox = x;
-            let oy = y;
+    #[test]
+    #[should_panic(expected = "the mask size does not match the source size")]
+    fn blit_masked_checks_mask_size() {
+        let mut source = [0u32; 4];
+        let source = Canvas::new(&mut source, 2, 2);
+        let mut buffer = [0u32; 4];
+        Canvas::new(&mut buffer, 2, 2).blit_masked(&source, &[255; 3], 0, 0);
+    }
 
-            while y != 0 && self.buffer[(y - 1) * self.width + x] == seed_color {
-                y -= 1;
-            }
-            while x != 0 && self.buffer[y * self.width + (x - 1)] == seed_color {
-                x -= 1;
-            }
+    #[test]
+    fn xor_drawing_twice_restores_pixels() {
+        let mut buffer: Vec<u32> = (0..32 * 32)
+            .map(|i: u32| i.wrapping_mul(0x0101_0107))
+            .collect();
+        let original = buffer.clone();
+        let mut canvas = Canvas::new(&mut buffer, 32, 32);
+        canvas.set_raster_op(RasterOp::Xor);
+
+        for _ in 0..2 {
+            canvas.set_pixel(3, 3, Color::RED);
+            canvas.fill_rect(-4, 2, 10, 9, Color::GREEN);
+            canvas.thick_outline_rect(5, 5, 20, 12, 3, Color::BLUE);
+            canvas.fill_circle(16, 16, 9, Color::WHITE);
+            canvas.outline_circle(10, 20, 13, Color::YELLOW);
+            canvas.fill_ellipse(20, 8, 11, 5, Color::CYAN);
+            canvas.outline_ellipse(12, 12, 4, 14, Color::MAGENTA);
+            canvas.fill_triangle(0, 0, 31, 7, 9, 30, Color::RED);
+            canvas.line(-3, 40, 35, -2, Color::WHITE);
+            canvas.thick_line(2, 29, 29, 4, 5, Color::GREEN);
+            canvas.draw_text(1, 24, "xor", Color::WHITE);
+        }
 
-            if x == ox && y == oy {
-                break;
-            }
+        assert_eq!(buffer, original);
+    }
+
+    #[test]
+    fn fill_circle_writes_each_pixel_once() {
+        for &(x, y, r) in &[(8, 8, 1), (8, 8, 7), (3, 12, 6), (-2, 5, 9), (15, 0, 4)] {
+            let mut copied = [0u32; 256];
+            Canvas::new(&mut copied, 16, 16).fill_circle(x, y, r, Color::RED);
+
+            let mut xored = [0u32; 256];
+            let mut canvas = Canvas::new(&mut xored, 16, 16);
+            canvas.set_raster_op(RasterOp::Xor);
+            canvas.fill_circle(x, y, r, Color::RED);
+
+            let mut added = [0u32; 256];
+            Canvas::new(&mut added, 16, 16).fill_circle_add(x, y, r, Color::rgb(1, 0, 0));
+            let once = copied.map(|p| if p == 0 { 0 } else { Color::rgb(1, 0, 0).0 });
+
+            assert_eq!(xored, copied, "circle at ({}, {}) r = {}", x, y, r);
+            assert_eq!(added, once, "circle at ({}, {}) r = {}", x, y, r);
         }
+    }
 
-        self.flood_fill_core(x, y, seed_color, raw_color);
+    #[test]
+    #[should_panic(expected = "width * height does not match the buffer size")]
+    fn canvas_size_overflow_panics() {
+        // width * height wraps around to 0 if it is not checked
+        let mut buffer = [];
+        let _ = Canvas::new(&mut buffer, usize::MAX / 2 + 1, 2);
     }
 
-    fn flood_fill_core(&mut self, mut x: usize, mut y: usize, seed_color: u32, raw_color: u32) {
-        let mut last_row_len = 0;
+    #[test]
+    fn extreme_coordinates_do_not_overflow() {
+        let mut buffer = [0u32; 16];
+        let mut canvas = Canvas::new(&mut buffer, 4, 4);
+        let (min, max) = (i32::MIN, i32::MAX);
+
+        canvas.hline(1, max - 1, max, Color::RED);
+        canvas.hline(1, min, min + 1, Color::RED);
+        canvas.vline(1, max - 1, max, Color::RED);
+        canvas.vline(1, min, min + 1, Color::RED);
+        canvas.fill_rect(max, max, max, max, Color::RED);
+        canvas.fill_rect(min, min, 1, 1, Color::RED);
+        canvas.fill_circle(max, min, 3, Color::RED);
+        canvas.fill_circle(min, max, 3, Color::RED);
+        canvas.fill_ellipse(min, 0, 2, 2, Color::RED);
+        canvas.fill_ellipse(0, max, 2, 2, Color::RED);
+        canvas.set_pixel(max, min, Color::RED);
+        assert_eq!(canvas.buffer(), [0; 16]);
+
+        canvas.hline(1, min, max, Color::RED);
+        canvas.vline(2, min, max, Color::RED);
+        let red: Vec<_> = (0..16).filter(|&i| buffer[i] == Color::RED.0).collect();
+        assert_eq!(red, [2, 4, 5, 6, 7, 10, 14]);
+    }
 
-        loop {
-            let mut row_len = 0;
-            let mut sx = x;
-
-            if last_row_len != 0 && self.buffer[y * self.width + x] != seed_color {
-                loop {
-                    last_row_len -= 1;
-                    if last_row_len == 0 {
-                        return;
-                    }
-                    x += 1;
-                    if self.buffer[y * self.width + x] == seed_color {
-                        break;
-                    }
-                }
-                sx = x;
-            } else {
-                while x != 0 && self.buffer[y * self.width + x - 1] == seed_color {
-                    x -= 1;
-                    self.buffer[y * self.width + x] = raw_color;
-                    if y != 0 && self.buffer[(y - 1) * self.width + x] == seed_color {
-                        self.flood_fill_start(x, y - 1, seed_color, raw_color);
-                    }
-                    row_len += 1;
-                    last_row_len += 1;
-                }
-            }
+    #[test]
+    fn alpha_blending_composites_once() {
+        let src = Color::rgba(200, 100, 0, 64);
+        assert_eq!(src.blend_over(Color(0)), src);
+        assert_eq!(src.blend_over(Color::WHITE), Color::rgb(241, 216, 191));
+        assert_eq!(Color::rgba(9, 9, 9, 0).blend_over(Color::RED), Color::RED);
+
+        let mut buffer = [Color::WHITE.0; 256];
+        let mut canvas = Canvas::new(&mut buffer, 16, 16);
+        canvas.set_blend_mode(BlendMode::Alpha);
+        canvas.fill_circle(8, 8, 6, src);
+        canvas.fill_rect(0, 0, 4, 4, src);
+
+        assert_eq!(buffer[8 * 16 + 8], Color::rgb(241, 216, 191).0);
+        assert_eq!(buffer[16 + 1], Color::rgb(241, 216, 191).0);
+    }
 
-            while sx < self.width && self.buffer[y * self.width + sx] == seed_color {
-                self.buffer[y * self.width + sx] = raw_color;
-                row_len += 1;
-                sx += 1;
-            }
+    // Renders `rows` (where '#' marks a set pixel) into a buffer.
+    fn reference_buffer(rows: &[&str]) -> Vec<u32> {
+        rows.iter()
+            .flat_map(|row| row.chars())
+            .map(|c| if c == '#' { Color::RED.0 } else { 0 })
+            .collect()
+    }
 
-            if row_len < last_row_len {
-                let end = x + last_row_len;
+    #[test]
+    fn fill_polygon_matches_reference() {
+        let check = |points: &[(i32, i32)], rows: [&str; 8]| {
+            let mut buffer = [0u32; 64];
+            Canvas::new(&mut buffer, 8, 8).fill_polygon(points, Color::RED);
+            assert_eq!(buffer.to_vec(), reference_buffer(&rows), "{:?}", points);
+        };
+
+        // concave
+        check(
+            &[(1, 1), (7, 1), (7, 7), (5, 7), (5, 3), (1, 3)],
+            [
+                "........", ".######.", ".######.", ".....##.", ".....##.", ".....##.", ".....##.",
+                "........",
+            ],
+        );
+
+        // self-intersecting bowtie
+        check(
+            &[(0, 0), (8, 8), (8, 0), (0, 8)],
+            [
+                "........", "#......#", "##....##", "###..###", "########", "###..###", "##....##",
+                "#......#",
+            ],
+        );
+
+        // a square inside a square, as one path: the inner one is a hole
+        check(
+            &[
+                (0, 0),
+                (8, 0),
+                (8, 8),
+                (0, 8),
+                (0, 0),
+                (2, 2),
+                (6, 2),
+                (6, 6),
+                (2, 6),
+                (2, 2),
+            ],
+            [
+                "########", "########", "##....##", "##....##", "##....##", "##....##", "########",
+                "########",
+            ],
+        );
+
+        let mut buffer = [0u32; 64];
+        let mut canvas = Canvas::new(&mut buffer, 8, 8);
+        canvas.fill_polygon(&[(0, 0), (8, 8)], Color::RED);
+        canvas.fill_polygon(&[(0, 0), (8, 0), (8, 0)], Color::RED);
+        assert_eq!(buffer, [0; 64]);
+
+        // the crossings of edges spanning the whole i32 range don't overflow
+        Canvas::new(&mut buffer, 8, 8).fill_polygon(
+            &[
+                (i32::MAX, i32::MIN),
+                (i32::MIN, i32::MAX),
+                (i32::MAX, i32::MAX),
+            ],
+            Color::RED,
+        );
+        assert_eq!(buffer, [Color::RED.0; 64]);
+    }
 
-                loop {
-                    sx += 1;
-                    if sx >= end {
-                        break;
-                    }
-                    if self.buffer[y * self.width + sx] == seed_color {
-                        self.flood_fill_core(sx, y, seed_color, raw_color);
-                    }
+    #[test]
+    fn bezier_flattening() {
+        // a straight curve flattens into its chord
+        assert_eq!(
+            flatten_quadratic_bezier((0, 0), (5, 5), (10, 10)),
+            vec![(0, 0), (10, 10)]
+        );
+
+        // a curve collapsing into a single point still draws it
+        assert_eq!(flatten_cubic_bezier([3.0; 8]), vec![(3, 3), (3, 3)]);
+
+        // the subdivision depth is bounded, even for absurd control points
+        let points = flatten_cubic_bezier([0.0, 0.0, 1e12, -1e12, -1e12, 1e12, 1.0, 1.0]);
+        assert!(points.len() <= (1 << BEZIER_MAX_DEPTH) + 1);
+        assert_eq!(points.first(), Some(&(0, 0)));
+        assert_eq!(points.last(), Some(&(1, 1)));
+
+        let points = flatten_cubic_bezier([0.0, 0.0, f64::NAN, 0.0, 0.0, 0.0, 4.0, 0.0]);
+        assert_eq!(points, vec![(0, 0), (4, 0)]);
+    }
+
+    #[test]
+    fn arc_and_pie_sweeps() {
+        use std::f32::consts::{PI, TAU};
+
+        let draw = |f: &dyn Fn(&mut Canvas)| {
+            let mut buffer = vec![0u32; 32 * 32];
+            f(&mut Canvas::new(&mut buffer, 32, 32));
+            buffer
+        };
+        let count = |buffer: &[u32]| buffer.iter().filter(|&&p| p != 0).count();
+
+        // the same sweep, however the angles are written
+        let arc = draw(&|c| c.outline_arc(16, 16, 10, 5.5, 0.5, Color::RED));
+        assert_eq!(
+            arc,
+            draw(&|c| c.outline_arc(16, 16, 10, 5.5 - TAU, 0.5, Color::RED))
+        );
+        assert_eq!(
+            arc,
+            draw(&|c| c.outline_arc(16, 16, 10, 5.5 + TAU, 0.5 + 2.0 * TAU, Color::RED))
+        );
+        assert_eq!(arc[16 * 32 + 26], Color::RED.0);
+        assert_eq!(arc[16 * 32 + 6], 0);
+
+        // a full turn draws every pixel of the outline exactly once
+        let full = draw(&|c| {
+            c.set_raster_op(RasterOp::Xor);
+            c.outline_arc(16, 16, 10, 1.0, 1.0 + TAU, Color::RED);
+        });
+        assert_eq!(full[6 * 32 + 16], Color::RED.0);
+        assert_eq!(full[26 * 32 + 16], Color::RED.0);
+        let halves = draw(&|c| {
+            c.outline_arc(16, 16, 10, 0.0, PI, Color::RED);
+            c.outline_arc(16, 16, 10, PI, TAU, Color::RED);
+        });
+        assert_eq!(full, halves);
+
+        // the pie crossing 0 covers the right side, but not the left one
+        let pie = draw(&|c| c.fill_pie(16, 16, 10, 1.5 * PI, 0.5 * PI, Color::RED));
+        assert_eq!(pie[16 * 32 + 16], Color::RED.0);
+        assert_eq!(pie[10 * 32 + 22], Color::RED.0);
+        assert_eq!(pie[16 * 32 + 10], 0);
+
+        // a sliver still fills its radius
+        let sliver = draw(&|c| c.fill_pie(16, 16, 10, 0.0, 0.0001, Color::RED));
+        assert!((16..26).all(|x| sliver[16 * 32 + x] == Color::RED.0));
+        assert_eq!(count(&sliver), 10);
+
+        // invalid angles and radii draw nothing
+        assert_eq!(
+            count(&draw(&|c| c.fill_pie(16, 16, 0, 0.0, PI, Color::RED))),
+            0
+        );
+        assert_eq!(
+            count(&draw(&|c| c.outline_arc(
+                16,
+                16,
+                10,
+                f32::NAN,
+                PI,
+                Color::RED
+            ))),
+            0
+        );
+    }
+
+    #[test]
+    fn anti_aliased_circles() {
+        let mut aliased = [0u32; 32 * 32];
+        Canvas::new(&mut aliased, 32, 32).fill_circle(16, 16, 9, Color::WHITE);
+
+        let mut buffer = [0u32; 32 * 32];
+        let mut canvas = Canvas::new(&mut buffer, 32, 32);
+        canvas.clear(Color::BLACK);
+        canvas.fill_circle_aa(16, 16, 9, Color::WHITE);
+
+        // the interior is solid, only a thin ring around it is blended
+        let gray = |p: u32| Color(p).to_rgb().0;
+        assert!(buffer
+            .iter()
+            .enumerate()
+            .filter(|&(_, &p)| p != Color::WHITE.0 && p != Color::BLACK.0)
+            .all(|(i, &p)| gray(p) > 0 && gray(p) < 255 && {
+                let (dx, dy) = ((i % 32) as f64 - 16.0, (i / 32) as f64 - 16.0);
+                ((dx * dx + dy * dy).sqrt() - 9.0).abs() <= 0.5 + std::f64::consts::FRAC_1_SQRT_2
+            }));
+        assert_eq!(buffer[16 * 32 + 16], Color::WHITE.0);
+        assert_eq!(buffer[16 * 32 + 26], Color::BLACK.0);
+        // the solid part never reaches further than the aliased circle
+        assert!(buffer
+            .iter()
+            .zip(&aliased)
+            .all(|(&p, &a)| p != Color::WHITE.0 || a == Color::WHITE.0));
+
+        // the outline fades out on both sides, the center stays untouched
+        let mut canvas = Canvas::new(&mut buffer, 32, 32);
+        canvas.clear(Color::BLACK);
+        canvas.outline_circle_aa(16, 16, 9, Color::WHITE);
+        assert_eq!(buffer[16 * 32 + 25], Color::WHITE.0);
+        assert_eq!(buffer[16 * 32 + 16], Color::BLACK.0);
+        assert_eq!(buffer[16 * 32 + 27], Color::BLACK.0);
+
+        // clipped and degenerate circles
+        let mut canvas = Canvas::new(&mut buffer, 32, 32);
+        canvas.clear(Color::BLACK);
+        canvas.fill_circle_aa(16, 16, 0, Color::WHITE);
+        canvas.outline_circle_aa(16, 16, -3, Color::WHITE);
+        canvas.fill_circle_aa(i32::MAX, i32::MIN, 5, Color::WHITE);
+        assert!(buffer.iter().all(|&p| p == Color::BLACK.0));
+        let mut canvas = Canvas::new(&mut buffer, 32, 32);
+        canvas.fill_circle_aa(0, 0, 40, Color::WHITE);
+        assert_eq!(buffer[31 * 32 + 20], Color::WHITE.0);
+
+        // the edge ring is the same in all four directions
+        for r in 1..10 {
+            for &outline in &[false, true] {
+                let mut canvas = Canvas::new(&mut buffer, 32, 32);
+                canvas.clear(Color::BLACK);
+                if outline {
+                    canvas.outline_circle_aa(16, 16, r, Color::WHITE);
+                } else {
+                    canvas.fill_circle_aa(16, 16, r, Color::WHITE);
                 }
-            } else if row_len > last_row_len && y != 0 {
-                let mut ux = x + last_row_len;
-                loop {
-                    ux += 1;
-                    if ux >= sx {
-                        break;
-                    }
-                    if self.buffer[(y - 1) * self.width + ux] == seed_color {
-                        self.flood_fill_start(ux, y - 1, seed_color, raw_color);
+                for (i, &p) in buffer.iter().enumerate() {
+                    let (x, y) = (i % 32, i / 32);
+                    if x == 0 || y == 0 {
+                        continue;
                     }
+                    assert_eq!(p, buffer[x * 32 + y], "r = {}, outline = {}", r, outline);
+                    assert_eq!(
+                        p,
+                        buffer[y * 32 + 32 - x],
+                        "r = {}, outline = {}",
+                        r,
+                        outline
+                    );
+                    assert_eq!(
+                        p,
+                        buffer[(32 - y) * 32 + x],
+                        "r = {}, outline = {}",
+                        r,
+                        outline
+                    );
                 }
             }
+        }
+    }
 
-            last_row_len = row_len;
+    #[test]
+    fn dashed_lines_follow_line() {
+        let draw = |f: &dyn Fn(&mut Canvas)| {
+            let mut buffer = vec![0u32; 16 * 16];
+            f(&mut Canvas::new(&mut buffer, 16, 16));
+            buffer
+        };
+
+        // without gaps, or with dashes longer than the line, it is just a line
+        let line = draw(&|c| c.line(1, 2, 14, 9, Color::RED));
+        assert_eq!(
+            line,
+            draw(&|c| c.dashed_line(1, 2, 14, 9, 1, 0, Color::RED))
+        );
+        assert_eq!(
+            line,
+            draw(&|c| c.dashed_line(1, 2, 14, 9, 3, -4, Color::RED))
+        );
+        assert_eq!(
+            line,
+            draw(&|c| c.dashed_line(1, 2, 14, 9, 100, 5, Color::RED))
+        );
+
+        // the dashes lie on the solid line
+        let dashes = draw(&|c| c.dashed_line(1, 2, 14, 9, 2, 3, Color::RED));
+        let solid = draw(&|c| c.dashed_line(1, 2, 14, 9, 5, 0, Color::BLUE));
+        let dash_count = dashes.iter().filter(|&&p| p != 0).count();
+        assert_eq!(dash_count, 6);
+        assert!(dashes
+            .iter()
+            .zip(&solid)
+            .all(|(&d, &s)| d == 0 || s == Color::BLUE.0));
+
+        // the pattern is the same wherever the line is, even partly outside of the canvas
+        let shifted = draw(&|c| c.dashed_line(-4, 2, 9, 9, 2, 3, Color::RED));
+        assert!((0..16).all(|y| (5..16).all(|x| dashes[y * 16 + x] == shifted[y * 16 + x - 5])));
+
+        // joined segments continue the pattern of one line through them
+        let continuous = draw(&|c| c.dashed_line(1, 1, 14, 14, 3, 2, Color::RED));
+        let joined = draw(&|c| {
+            let phase = c.dashed_line_with_phase(1, 1, 7, 7, 3, 2, 0, Color::RED);
+            assert_eq!(phase, 1);
+            c.dashed_line_with_phase(7, 7, 14, 14, 3, 2, phase, Color::RED);
+        });
+        assert_eq!(joined, continuous);
+        let restarted = draw(&|c| {
+            c.dashed_line(1, 1, 7, 7, 3, 2, Color::RED);
+            c.dashed_line(7, 7, 14, 14, 3, 2, Color::RED);
+        });
+        assert_ne!(restarted, continuous);
+
+        assert_eq!(
+            draw(&|c| c.dashed_line(1, 2, 14, 9, 0, 3, Color::RED)),
+            [0; 256]
+        );
+        assert_eq!(draw(&|c| c.dotted_line(1, 2, 14, 9, -1, Color::RED)), line);
+    }
 
-            y += 1;
-            if last_row_len == 0 || y >= self.height {
-                break;
+    // One of every shape, each drawn with a single call (or one pen path) on a 64 x 64 canvas by draw_shape.
+    const SHAPES: &[&str] = &[
+        "set_pixel",
+        "fill_rect",
+        "fill_rect_f32",
+        "outline_rect",
+        "thick_outline_rect",
+        "outline_rect_row",
+        "thick_outline_rect_narrow",
+        "fill_circle",
+        "fill_circle_add",
+        "outline_circle",
+        "thick_outline_circle",
+        "fill_ellipse",
+        "outline_ellipse",
+        "fill_triangle",
+        "outline_triangle",
+        "thick_outline_triangle",
+        "hline",
+        "vline",
+        "thick_hline",
+        "thick_vline",
+        "line",
+        "line_f32",
+        "thick_line",
+        "fill_polygon",
+        "fill_regular_polygon",
+        "outline_regular_polygon",
+        "polyline",
+        "polygon_outline",
+        "thick_polyline",
+        "quadratic_bezier",
+        "thick_quadratic_bezier",
+        "cubic_bezier",
+        "thick_cubic_bezier",
+        "outline_arc",
+        "fill_pie",
+        "fill_circle_aa",
+        "outline_circle_aa",
+        "dashed_line",
+        "draw_text",
+        "draw_text_rotated",
+        "pen_corner",
+        "pen_star",
+        "pen_polygon",
+    ];
+
+    fn draw_shape(c: &mut Canvas, shape: &str, color: Color) {
+        const POINTS: &[(i32, i32)] = &[(2, 2), (60, 10), (30, 30), (50, 60), (5, 40)];
+        match shape {
+            "set_pixel" => c.set_pixel(3, 4, color),
+            "fill_rect" => c.fill_rect(5, 6, 30, 20, color),
+            "fill_rect_f32" => c.fill_rect_f32(5.5, 6.2, 30.0, 20.7, color),
+            "outline_rect" => c.outline_rect(5, 6, 30, 20, color),
+            "thick_outline_rect" => c.thick_outline_rect(5, 6, 40, 30, 5, color),
+            "outline_rect_row" => c.outline_rect(5, 6, 30, 1, color),
+            "thick_outline_rect_narrow" => c.thick_outline_rect(5, 6, 3, 30, 6, color),
+            "fill_circle" => c.fill_circle(30, 30, 17, color),
+            "fill_circle_add" => c.fill_circle_add(30, 30, 17, color),
+            "outline_circle" => c.outline_circle(30, 30, 17, color),
+            "thick_outline_circle" => c.thick_outline_circle(30, 30, 17, 6, color),
+            "fill_ellipse" => c.fill_ellipse(30, 30, 25, 11, color),
+            "outline_ellipse" => c.outline_ellipse(30, 30, 25, 11, color),
+            "fill_triangle" => c.fill_triangle(3, 5, 60, 20, 20, 58, color),
+            "outline_triangle" => c.outline_triangle(3, 5, 60, 20, 20, 58, color),
+            "thick_outline_triangle" => c.thick_outline_triangle(3, 5, 60, 20, 20, 58, 5, color),
+            "hline" => c.hline(7, 3, 50, color),
+            "vline" => c.vline(7, 3, 50, color),
+            "thick_hline" => c.thick_hline(7, 3, 50, 4, color),
+            "thick_vline" => c.thick_vline(7, 3, 50, 4, color),
+            "line" => c.line(3, 5, 60, 41, color),
+            "line_f32" => c.line_f32(3.2, 5.7, 60.1, 41.0, color),
+            "thick_line" => c.thick_line(3, 5, 60, 41, 6, color),
+            "fill_polygon" => c.fill_polygon(POINTS, color),
+            "fill_regular_polygon" => c.fill_regular_polygon(32, 32, 25, 7, 0.3, color),
+            "outline_regular_polygon" => c.outline_regular_polygon(32, 32, 25, 7, 0.3, color),
+            "polyline" => c.polyline(POINTS, color),
+            "polygon_outline" => c.polygon_outline(POINTS, color),
+            "thick_polyline" => c.thick_polyline(POINTS, 5, color),
+            "quadratic_bezier" => c.quadratic_bezier(2, 60, 30, -40, 60, 60, color),
+            "thick_quadratic_bezier" => c.thick_quadratic_bezier(2, 60, 30, -40, 60, 60, 5, color),
+            "cubic_bezier" => c.cubic_bezier(2, 2, 90, 2, -30, 60, 60, 60, color),
+            "thick_cubic_bezier" => c.thick_cubic_bezier(2, 2, 90, 2, -30, 60, 60, 60, 5, color),
+            "outline_arc" => c.outline_arc(30, 30, 20, 5.0, 2.0, color),
+            "fill_pie" => c.fill_pie(30, 30, 20, 5.0, 2.0, color),
+            "fill_circle_aa" => c.fill_circle_aa(30, 30, 17, color),
+            "outline_circle_aa" => c.outline_circle_aa(30, 30, 17, color),
+            "dashed_line" => c.dashed_line(3, 5, 60, 41, 4, 3, color),
+            "draw_text" => c.draw_text(2, 2, "Hi, xor!", color),
+            "draw_text_rotated" => c.draw_text_rotated(10, 5, "Hi, xor!", 37.0, color),
+            "pen_corner" => {
+                let mut pen = c.pen();
+                pen.set_position(5.0, 5.0).set_color(color).set_thickness(6);
+                pen.forward(40.0).turn_right(90.0).forward(40.0);
+            }
+            "pen_star" => {
+                let mut pen = c.pen();
+                pen.set_position(8.0, 25.0)
+                    .set_color(color)
+                    .set_thickness(5);
+                pen.star(5, 45.0);
             }
+            "pen_polygon" => {
+                let mut pen = c.pen();
+                pen.set_position(20.0, 10.0).set_color(color);
+                pen.polygon(6, 20.0);
+            }
+            _ => unreachable!(),
         }
     }
 
-    #[allow(clippy::similar_names)]
-    #[inline]
-    fn clamp_rect_i32(&self, xmin: i32, xmax: i32, ymin: i32, ymax: i32) -> (i32, i32, i32, i32) {
-        let from_x = xmin.clamp(0, self.clamped_width - 1);
-        let to_x = xmax.clamp(from_x, self.clamped_width);
+    #[test]
+    fn xor_draws_match_copy_draws() {
+        // a single XOR draw onto 0 only differs from a copy if a pixel is drawn more than once
+        let color = Color::rgb(200, 100, 50);
+        for &shape in SHAPES {
+            let mut copy = vec![0u32; 64 * 64];
+            draw_shape(&mut Canvas::new(&mut copy, 64, 64), shape, color);
 
-        let from_y = ymin.clamp(0, self.clamped_height - 1);
-        let to_y = ymax.clamp(from_y, self.clamped_height);
+            let mut xor = vec![0u32; 64 * 64];
+            let mut canvas = Canvas::new(&mut xor, 64, 64);
+            canvas.set_raster_op(RasterOp::Xor);
+            draw_shape(&mut canvas, shape, color);
 
-        (from_x, to_x, from_y, to_y)
+            assert!(copy == xor, "{} draws some pixels more than once", shape);
+        }
     }
 
-    #[allow(clippy::cast_sign_loss)]
-    #[inline]
-    unsafe fn set_pixel_unchecked_raw_i32(&mut self, x: i32, y: i32, raw_color: u32) {
-        debug_assert!(x >= 0 && y >= 0);
-        let idx = y as usize * self.width + x as usize;
-
-        debug_assert!(idx < self.buffer.len());
-        *self.buffer.get_unchecked_mut(idx) = raw_color;
+    #[test]
+    fn alpha_draws_composite_once() {
+        // a 50% color composited more than once leaves a pixel darker than a single composite
+        let color = Color::rgba(0, 0, 0, 128);
+        let once = color.blend_over(Color::WHITE);
+        for &shape in SHAPES {
+            let mut buffer = vec![Color::WHITE.0; 64 * 64];
+            let mut canvas = Canvas::new(&mut buffer, 64, 64);
+            canvas.set_blend_mode(BlendMode::Alpha);
+            draw_shape(&mut canvas, shape, color);
+
+            assert!(
+                buffer
+                    .iter()
+                    .all(|&p| Color(p).luminance() >= once.luminance()),
+                "{} composites some pixels more than once",
+                shape
+            );
+        }
     }
 }
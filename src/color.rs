@@ -1,9 +1,44 @@
+use std::{
+    fmt,
+    ops::{Add, AddAssign},
+    str::FromStr,
+};
+
 /// A tuple struct that represents a color.
 /// This struct has a single public field, which stores
-/// the color as a u32.
+/// the color as a u32 in the crate's [`PixelFormat`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Color(pub u32);
 
+/// The one pixel format used throughout the crate, by [`Color`], every buffer and the image encoders.
+///
+/// A pixel is a `u32` of the form `0xAARRGGBB`, so its bytes are b, g, r, a in little-endian memory
+/// (`u32::to_le_bytes`), which is what e.g. minifb expects. Alpha 255 is opaque, alpha 0 is fully transparent.
+/// Index into a buffer with this in mind, or better, go through [`pack`](PixelFormat::pack) and [`unpack`](PixelFormat::unpack).
+/// ```rust
+/// use vason::{color::PixelFormat, Color};
+///
+/// assert_eq!(PixelFormat::pack(0x12, 0x34, 0x56, 0x78), 0x7812_3456);
+/// assert_eq!(PixelFormat::unpack(Color::rgb(1, 2, 3).0), (1, 2, 3, 255));
+/// assert_eq!(Color::rgb(1, 2, 3).0.to_le_bytes(), [3, 2, 1, 255]);
+/// ```
+pub struct PixelFormat;
+
+impl PixelFormat {
+    /// Packs r, g, b and a values into a pixel.
+    #[must_use]
+    pub const fn pack(r: u8, g: u8, b: u8, a: u8) -> u32 {
+        u32::from_le_bytes([b, g, r, a])
+    }
+
+    /// Returns the (r, g, b, a) values of a pixel.
+    #[must_use]
+    pub const fn unpack(pixel: u32) -> (u8, u8, u8, u8) {
+        let [b, g, r, a] = pixel.to_le_bytes();
+        (r, g, b, a)
+    }
+}
+
 impl Color {
     /// Creates a [`Color`] from r, g and b values.
     ///
@@ -17,7 +52,23 @@ impl Color {
     /// ```
     #[must_use]
     pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
-        Self(u32::from_le_bytes([b, g, r, 255]))
+        Self::rgba(r, g, b, 255)
+    }
+
+    /// Creates a [`Color`] from r, g, b and a values. An alpha of 255 is opaque.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::Color;
+    ///
+    /// let color = Color::rgba(255, 0, 0, 128);
+    /// assert_eq!(color.0, 0x80ff_0000);
+    /// assert_eq!(color.to_rgba(), (255, 0, 0, 128));
+    /// ```
+    #[must_use]
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self(PixelFormat::pack(r, g, b, a))
     }
 
     /// Returns a tuple of (r,g,b) values.
@@ -32,10 +83,319 @@ impl Color {
     /// ```
     #[must_use]
     pub const fn to_rgb(self) -> (u8, u8, u8) {
-        let [b, g, r, _] = u32::to_le_bytes(self.0);
+        let (r, g, b, _) = self.to_rgba();
         (r, g, b)
     }
 
+    /// Returns a tuple of (r,g,b,a) values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::Color;
+    ///
+    /// assert_eq!(Color::YELLOW.to_rgba(), (255, 255, 0, 255));
+    /// ```
+    #[must_use]
+    pub const fn to_rgba(self) -> (u8, u8, u8, u8) {
+        PixelFormat::unpack(self.0)
+    }
+
+    /// Returns the luminance of this [`Color`] (Rec. 709 weights applied to the r, g and b values, alpha is ignored).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::Color;
+    ///
+    /// assert_eq!(Color::WHITE.luminance(), 255);
+    /// assert_eq!(Color::BLACK.luminance(), 0);
+    /// assert_eq!(Color::gray(77).luminance(), 77);
+    /// assert!(Color::GREEN.luminance() > Color::RED.luminance());
+    /// ```
+    #[allow(clippy::cast_possible_truncation)]
+    #[must_use]
+    pub const fn luminance(self) -> u8 {
+        let (r, g, b) = self.to_rgb();
+        // the weights add up to 10000, so the result always fits in a u8
+        ((r as u32 * 2126 + g as u32 * 7152 + b as u32 * 722 + 5000) / 10000) as u8
+    }
+
+    /// Returns the (r, g, b, a) channels of this [`Color`] in linear light, in the range 0.0..=1.0.
+    /// The r, g and b values are decoded from sRGB, alpha is just scaled.
+    /// Blending and averaging colors is more correct in linear light.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::Color;
+    ///
+    /// let (r, g, b, a) = Color::rgb(255, 128, 0).to_linear();
+    /// assert_eq!((r, b, a), (1.0, 0.0, 1.0));
+    /// assert!((g - 0.2158).abs() < 0.001);
+    /// ```
+    #[must_use]
+    pub fn to_linear(self) -> (f32, f32, f32, f32) {
+        let (r, g, b, a) = self.to_rgba();
+        (
+            srgb_to_linear(r),
+            srgb_to_linear(g),
+            srgb_to_linear(b),
+            f32::from(a) / 255.0,
+        )
+    }
+
+    /// Creates a [`Color`] from linear light (r, g, b, a) channels in the range 0.0..=1.0 (values outside are clamped).
+    /// This is the inverse of [`to_linear`](Color::to_linear).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::Color;
+    ///
+    /// let color = Color::rgb(12, 128, 250);
+    /// let (r, g, b, a) = color.to_linear();
+    /// assert_eq!(Color::from_linear(r, g, b, a), color);
+    /// ```
+    #[must_use]
+    pub fn from_linear(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self::rgba(
+            linear_to_srgb(r),
+            linear_to_srgb(g),
+            linear_to_srgb(b),
+            unit_to_u8(a),
+        )
+    }
+
+    /// Returns the inverse of this [`Color`]: every r, g and b value `c` becomes `255 - c`, alpha is kept.
+    /// Inverting twice gives back the original color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::Color;
+    ///
+    /// assert_eq!(Color::RED.invert(), Color::CYAN);
+    /// assert_eq!(Color::GOLD.invert().invert(), Color::GOLD);
+    /// ```
+    #[must_use]
+    pub const fn invert(self) -> Self {
+        Self(self.0 ^ 0x00ff_ffff)
+    }
+
+    /// Creates an opaque [`Color`] from hue (in degrees, wrapping around at 360), saturation and value (both in 0.0..=1.0, clamped).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::Color;
+    ///
+    /// assert_eq!(Color::from_hsv(0.0, 1.0, 1.0), Color::RED);
+    /// assert_eq!(Color::from_hsv(480.0, 1.0, 1.0), Color::GREEN);
+    /// assert_eq!(Color::from_hsv(200.0, 0.0, 0.5), Color::gray(128));
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    #[must_use]
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Self {
+        let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+        let chroma = v * s;
+        let (r, g, b) = hue_to_rgb(h, chroma);
+        let m = v - chroma;
+        Self::rgb(unit_to_u8(r + m), unit_to_u8(g + m), unit_to_u8(b + m))
+    }
+
+    /// Creates an opaque [`Color`] from hue (in degrees, wrapping around at 360), saturation and lightness (both in 0.0..=1.0, clamped).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::Color;
+    ///
+    /// assert_eq!(Color::from_hsl(0.0, 1.0, 0.5), Color::RED);
+    /// assert_eq!(Color::from_hsl(240.0, 1.0, 0.25), Color::DARK_BLUE);
+    /// assert_eq!(Color::from_hsl(90.0, 0.7, 1.0), Color::WHITE);
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    #[must_use]
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Self {
+        let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let (r, g, b) = hue_to_rgb(h, chroma);
+        let m = l - chroma / 2.0;
+        Self::rgb(unit_to_u8(r + m), unit_to_u8(g + m), unit_to_u8(b + m))
+    }
+
+    /// Returns the (hue, saturation, value) of this [`Color`], alpha is ignored.
+    /// The hue is in degrees in 0.0..360.0 (0.0 for grays), saturation and value are in 0.0..=1.0.
+    /// This is the inverse of [`from_hsv`](Color::from_hsv), up to rounding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::Color;
+    ///
+    /// assert_eq!(Color::CYAN.to_hsv(), (180.0, 1.0, 1.0));
+    ///
+    /// // rotate the hue
+    /// let (h, s, v) = Color::RED.to_hsv();
+    /// assert_eq!(Color::from_hsv(h + 120.0, s, v), Color::GREEN);
+    /// ```
+    #[must_use]
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let (r, g, b) = self.to_rgb();
+        let (hue, max, min) = rgb_to_hue(r, g, b);
+        let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+        (hue, saturation, max)
+    }
+
+    /// Returns the (hue, saturation, lightness) of this [`Color`], alpha is ignored.
+    /// The hue is in degrees in 0.0..360.0 (0.0 for grays), saturation and lightness are in 0.0..=1.0.
+    /// This is the inverse of [`from_hsl`](Color::from_hsl), up to rounding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::Color;
+    ///
+    /// assert_eq!(Color::RED.to_hsl(), (0.0, 1.0, 0.5));
+    /// assert_eq!(Color::BLACK.to_hsl(), (0.0, 0.0, 0.0));
+    /// ```
+    #[allow(clippy::float_cmp)]
+    #[must_use]
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let (r, g, b) = self.to_rgb();
+        let (hue, max, min) = rgb_to_hue(r, g, b);
+        let lightness = (max + min) / 2.0;
+        let saturation = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+        (hue, saturation, lightness)
+    }
+
+    /// Linearly interpolates every channel (alpha included) between this [`Color`] and `other`.
+    /// `t` is clamped to 0.0..=1.0, 0.0 gives this color and 1.0 gives `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::Color;
+    ///
+    /// assert_eq!(Color::BLACK.lerp(Color::WHITE, 0.5), Color::gray(128));
+    /// assert_eq!(Color::RED.lerp(Color::BLUE, 2.0), Color::BLUE);
+    /// ```
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    #[must_use]
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let (from, to) = (self.0.to_le_bytes(), other.0.to_le_bytes());
+        let mut out = [0u8; 4];
+        for ((channel, a), b) in out.iter_mut().zip(from).zip(to) {
+            let (a, b) = (f32::from(a), f32::from(b));
+            *channel = (a + (b - a) * t).round() as u8;
+        }
+        Self(u32::from_le_bytes(out))
+    }
+
+    /// Composites this [`Color`] over the background `bg` using its alpha (source-over, neither color is premultiplied),
+    /// rounding to the nearest value. This is what drawing with [`BlendMode::Alpha`](crate::canvas::BlendMode::Alpha) does to every pixel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::Color;
+    ///
+    /// let glass = Color::rgba(0, 0, 255, 128);
+    /// assert_eq!(glass.blend_over(Color::WHITE), Color::rgb(127, 127, 255));
+    /// assert_eq!(Color::RED.blend_over(Color::WHITE), Color::RED);
+    /// assert_eq!(Color::rgba(0, 0, 0, 0).blend_over(Color::GREEN), Color::GREEN);
+    /// ```
+    #[allow(clippy::cast_possible_truncation)]
+    #[must_use]
+    pub fn blend_over(self, bg: Self) -> Self {
+        let (src_r, src_g, src_b, src_a) = self.to_rgba();
+        let (dst_r, dst_g, dst_b, dst_a) = bg.to_rgba();
+        match src_a {
+            255 => return self,
+            0 => return bg,
+            _ => {}
+        }
+
+        // the weights of the two colors, their sum is the alpha of the result times 255
+        let src_weight = u32::from(src_a) * 255;
+        let dst_weight = u32::from(dst_a) * (255 - u32::from(src_a));
+        let alpha = src_weight + dst_weight;
+        if alpha == 0 {
+            return Self(0);
+        }
+
+        let channel = |s: u8, d: u8| {
+            ((u32::from(s) * src_weight + u32::from(d) * dst_weight + alpha / 2) / alpha) as u8
+        };
+        Self::rgba(
+            channel(src_r, dst_r),
+            channel(src_g, dst_g),
+            channel(src_b, dst_b),
+            ((alpha + 127) / 255) as u8,
+        )
+    }
+
+    /// Parses a [`Color`] from a hex string: `"#rrggbb"`, `"#rgb"` shorthand (every digit is doubled)
+    /// or `"#rrggbbaa"`. The `#` is optional and the digits are case insensitive.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the number of digits is not 3, 6 or 8, or if one of them is not a hex digit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::{color::ColorParseError, Color};
+    ///
+    /// assert_eq!(Color::from_hex("#87CEFA"), Ok(Color::rgb(135, 206, 250)));
+    /// assert_eq!(Color::from_hex("f0f"), Ok(Color::MAGENTA));
+    /// assert_eq!(Color::from_hex("#ff000080"), Ok(Color::rgba(255, 0, 0, 128)));
+    /// assert_eq!(Color::from_hex("#ff00f"), Err(ColorParseError::InvalidLength(5)));
+    /// assert_eq!(Color::from_hex("#gg0000"), Err(ColorParseError::InvalidDigit('g')));
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Self, ColorParseError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+
+        let mut values = Vec::with_capacity(8);
+        for c in digits.chars() {
+            let value = c.to_digit(16).ok_or(ColorParseError::InvalidDigit(c))?;
+            #[allow(clippy::cast_possible_truncation)]
+            values.push(value as u8);
+        }
+
+        let channel = |i: usize| values[i] << 4 | values[i + 1];
+        match values.len() {
+            3 => Ok(Self::rgb(values[0] * 17, values[1] * 17, values[2] * 17)),
+            6 => Ok(Self::rgb(channel(0), channel(2), channel(4))),
+            8 => Ok(Self::rgba(channel(0), channel(2), channel(4), channel(6))),
+            len => Err(ColorParseError::InvalidLength(len)),
+        }
+    }
+
+    /// Returns this [`Color`] as a `"#rrggbb"` hex string, alpha is ignored.
+    /// This is the inverse of [`from_hex`](Color::from_hex) for opaque colors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vason::Color;
+    ///
+    /// assert_eq!(Color::SKY_BLUE.to_hex(), "#87cdfa");
+    /// assert_eq!(Color::from_hex(&Color::GOLD.to_hex()), Ok(Color::GOLD));
+    /// ```
+    #[allow(clippy::uninlined_format_args)]
+    #[must_use]
+    pub fn to_hex(self) -> String {
+        let (r, g, b) = self.to_rgb();
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+
     #[must_use]
     pub const fn gray(c: u8) -> Self {
         Self::rgb(c, c, c)
@@ -63,6 +423,96 @@ impl Color {
     pub const SKY_BLUE: Self = Self::rgb(135, 205, 250);
 }
 
+/// The error returned by [`Color::from_hex`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorParseError {
+    /// The string has this many digits instead of 3, 6 or 8.
+    InvalidLength(usize),
+    /// The string contains this character, which is not a hex digit.
+    InvalidDigit(char),
+}
+
+impl fmt::Display for ColorParseError {
+    #[allow(clippy::uninlined_format_args)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidLength(len) => write!(f, "expected 3, 6 or 8 hex digits, found {}", len),
+            Self::InvalidDigit(c) => write!(f, "invalid hex digit {:?}", c),
+        }
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
+fn srgb_to_linear(c: u8) -> f32 {
+    let c = f32::from(c) / 255.0;
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    unit_to_u8(if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    })
+}
+
+// The (r, g, b) of a fully saturated hue with the given chroma, before adding the lightness offset.
+fn hue_to_rgb(h: f32, chroma: f32) -> (f32, f32, f32) {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    match h {
+        h if h < 1.0 => (chroma, x, 0.0),
+        h if h < 2.0 => (x, chroma, 0.0),
+        h if h < 3.0 => (0.0, chroma, x),
+        h if h < 4.0 => (0.0, x, chroma),
+        h if h < 5.0 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    }
+}
+
+// Returns the hue (in degrees) and the largest and smallest channel (in 0.0..=1.0) of r, g and b.
+#[allow(clippy::float_cmp)]
+fn rgb_to_hue(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let (r, g, b) = (
+        f32::from(r) / 255.0,
+        f32::from(g) / 255.0,
+        f32::from(b) / 255.0,
+    );
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    let chroma = max - min;
+
+    let sector = if chroma == 0.0 {
+        0.0
+    } else if max == r {
+        (g - b) / chroma
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+    ((sector * 60.0).rem_euclid(360.0), max, min)
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn unit_to_u8(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl FromStr for Color {
+    type Err = ColorParseError;
+
+    /// See [`Color::from_hex`].
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(hex)
+    }
+}
+
 impl From<u32> for Color {
     fn from(value: u32) -> Self {
         Self(value)
@@ -87,6 +537,69 @@ impl From<Color> for u32 {
     }
 }
 
+/// Adds two colors channel by channel (alpha included), saturating at 255.
+/// ```rust
+/// use vason::Color;
+/// let glow = Color::rgb(200, 100, 0) + Color::rgb(100, 100, 100);
+///
+/// assert_eq!(glow, Color::rgb(255, 200, 100));
+/// ```
+impl Add for Color {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let (a, b) = (self.0.to_le_bytes(), other.0.to_le_bytes());
+        Self(u32::from_le_bytes([
+            a[0].saturating_add(b[0]),
+            a[1].saturating_add(b[1]),
+            a[2].saturating_add(b[2]),
+            a[3].saturating_add(b[3]),
+        ]))
+    }
+}
+
+impl AddAssign for Color {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+/// With the `serde` feature enabled, colors are (de)serialized as `"#rrggbb"` hex strings,
+/// or `"#rrggbbaa"` if the color is not fully opaque. Deserializing accepts everything [`Color::from_hex`] does.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Color {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (r, g, b, a) = self.to_rgba();
+        if a == 255 {
+            serializer.collect_str(&self.to_hex())
+        } else {
+            serializer.collect_str(&format_args!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a))
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Color {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct HexVisitor;
+
+        impl serde::de::Visitor<'_> for HexVisitor {
+            type Value = Color;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a color as a \"#rrggbb\", \"#rgb\" or \"#rrggbbaa\" hex string")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Color, E> {
+                Color::from_hex(value)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_str(HexVisitor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,4 +609,99 @@ mod tests {
         assert_eq!(u32::from(Color::rgb(12, 1, 231)), 4_278_976_999);
         assert_eq!(Color::from(786_919u32).to_rgb(), (12, 1, 231));
     }
+
+    #[test]
+    fn rgba_round_trips() {
+        let colors = [
+            Color::rgba(12, 1, 231, 255),
+            Color::rgba(255, 128, 0, 64),
+            Color::rgba(0, 0, 0, 0),
+        ];
+
+        for &color in &colors {
+            let (r, g, b, a) = color.to_rgba();
+            assert_eq!(Color::rgba(r, g, b, a), color);
+
+            // blending a color with itself or with nothing leaves it unchanged
+            assert_eq!(color.lerp(color, 0.5), color);
+            assert_eq!(color + Color(0), color);
+
+            // ppm keeps r, g and b, and decodes every pixel as opaque
+            let mut bytes = Vec::new();
+            crate::ppm::encode_buffer(&[color.0], 1, 1, &mut bytes).unwrap();
+            let (pixels, _, _) = crate::ppm::decode(&mut bytes.as_slice()).unwrap();
+            assert_eq!(Color(pixels[0]), Color::rgba(r, g, b, 255));
+        }
+    }
+
+    #[test]
+    fn lerp_and_blend_over_endpoints() {
+        let (a, b) = (Color::rgba(12, 200, 77, 3), Color::rgba(250, 0, 128, 255));
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+        assert_eq!(a.lerp(b, -1.0), a);
+        assert_eq!(a.lerp(b, f32::NAN), a);
+
+        // an opaque color hides the background, a transparent one shows it
+        assert_eq!(b.blend_over(a), b);
+        assert_eq!(Color::rgba(1, 2, 3, 0).blend_over(a), a);
+        assert_eq!(Color(0).blend_over(Color(0)), Color(0));
+        // over a transparent background, a translucent color stays the same
+        assert_eq!(a.blend_over(Color(0)), a);
+    }
+
+    #[test]
+    fn hsv_and_hsl_edge_cases() {
+        for &gray in &[0, 77, 128, 255] {
+            let l = f32::from(gray) / 255.0;
+            assert_eq!(Color::from_hsv(123.0, 0.0, l), Color::gray(gray));
+            assert_eq!(Color::from_hsl(321.0, 0.0, l), Color::gray(gray));
+            assert_eq!(Color::gray(gray).to_hsv(), (0.0, 0.0, l));
+            assert_eq!(Color::gray(gray).to_hsl(), (0.0, 0.0, l));
+        }
+
+        assert_eq!(Color::from_hsv(360.0, 1.0, 1.0), Color::RED);
+        assert_eq!(Color::from_hsl(360.0, 1.0, 0.5), Color::RED);
+        assert_eq!(Color::from_hsv(-120.0, 1.0, 1.0), Color::BLUE);
+        assert_eq!(Color::from_hsv(f32::EPSILON - 360.0, 1.0, 1.0), Color::RED);
+    }
+
+    #[test]
+    fn hsv_and_hsl_round_trip() {
+        for r in (0..=255).step_by(15) {
+            for g in (0..=255).step_by(17) {
+                for b in (0..=255).step_by(51) {
+                    let color = Color::rgb(r, g, b);
+                    let (h, s, v) = color.to_hsv();
+                    assert_eq!(Color::from_hsv(h, s, v), color);
+                    let (h, s, l) = color.to_hsl();
+                    assert_eq!(Color::from_hsl(h, s, l), color);
+                    assert!((0.0..360.0).contains(&h));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn linear_round_trip() {
+        for c in 0..=255u8 {
+            assert_eq!(linear_to_srgb(srgb_to_linear(c)), c);
+            assert_eq!(unit_to_u8(f32::from(c) / 255.0), c);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_hex() {
+        use serde::de::{value::Error, IntoDeserializer};
+        use serde::Deserialize;
+
+        let parse = |s: &str| Color::deserialize(IntoDeserializer::<Error>::into_deserializer(s));
+
+        assert_eq!(parse("#ff00ff").unwrap(), Color::MAGENTA);
+        assert_eq!(parse("87cdfa").unwrap(), Color::SKY_BLUE);
+        assert_eq!(parse("#ff000080").unwrap(), Color(0x80ff_0000));
+        assert!(parse("#ff00f").is_err());
+        assert!(parse("#gg0000").is_err());
+    }
 }
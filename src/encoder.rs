@@ -0,0 +1,33 @@
+//! A common interface for the image formats the crate can write, so the format can be picked at runtime.
+//! Every format module keeps its free functions as well; the encoders are thin wrappers around them.
+//! # Example
+//! ```rust
+//! use vason::{encoder::ImageEncoder, ppm, Canvas, Color};
+//!
+//! let mut buffer = vec![0u32; 64 * 64];
+//! let mut canvas = Canvas::new(&mut buffer, 64, 64);
+//! canvas.fill_circle(32, 32, 20, Color::WHITE);
+//!
+//! let grayscale = true;
+//! let encoder: &dyn ImageEncoder = if grayscale { &ppm::Pgm } else { &ppm::Ppm };
+//!
+//! let mut bytes = Vec::new();
+//! encoder.encode(&canvas, &mut bytes).expect("could not encode image");
+//! assert!(bytes.starts_with(b"P5"));
+//! ```
+
+use crate::Canvas;
+use std::io::{Result, Write};
+
+/// An image format a [`Canvas`] can be encoded to.
+pub trait ImageEncoder {
+    /// Encodes `canvas` and writes it to `w`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there was an i/o error whilest writing.
+    fn encode(&self, canvas: &Canvas, w: &mut dyn Write) -> Result<()>;
+
+    /// The file extension commonly used for this format, without the dot.
+    fn extension(&self) -> &'static str;
+}
@@ -0,0 +1,399 @@
+//! A built-in 8x8 monospace bitmap font, used by [`Canvas::draw_text`](crate::Canvas::draw_text).
+//! Every glyph is 8 rows of 8 pixels, where bit 7 of a row is its leftmost pixel.
+//! Printable ASCII (0x20–0x7E) is covered; control characters are blank and
+//! everything else is drawn with a hollow box ([`FALLBACK`]).
+//!
+//! A [`Font`] pairs such a glyph table with a per-glyph advance width, so proportional fonts can be used
+//! with [`Canvas::draw_text_with_font`](crate::Canvas::draw_text_with_font) as well.
+//! # Example
+//! ```rust
+//! use vason::font;
+//!
+//! let a = font::glyph('A');
+//! assert_eq!(a[0], 0b0011_1000);
+//! assert_eq!(font::glyph('é'), font::glyph('\u{7f}'));
+//! ```
+
+/// The width of a glyph (and the horizontal advance of the monospace font) in pixels.
+pub const GLYPH_WIDTH: i32 = 8;
+
+/// The height of a glyph (and the line height) in pixels.
+pub const GLYPH_HEIGHT: i32 = 8;
+
+/// The index of the glyph drawn for characters outside of ASCII.
+pub const FALLBACK: usize = 0x7f;
+
+/// The glyphs of the built-in font, indexed by ASCII code.
+pub const DEFAULT: [[u8; 8]; 128] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x00
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x01
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x02
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x03
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x04
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x05
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x06
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x07
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x08
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x09
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x0a
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x0b
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x0c
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x0d
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x0e
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x0f
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x10
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x11
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x12
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x13
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x14
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x15
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x16
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x17
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x18
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x19
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x1a
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x1b
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x1c
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x1d
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x1e
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x1f
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x20 ' '
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x00, 0x10, 0x00], // 0x21 '!'
+    [0x28, 0x28, 0x28, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x22 '"'
+    [0x28, 0x28, 0x7c, 0x28, 0x7c, 0x28, 0x28, 0x00], // 0x23 '#'
+    [0x10, 0x3c, 0x50, 0x38, 0x14, 0x78, 0x10, 0x00], // 0x24 '$'
+    [0x60, 0x64, 0x08, 0x10, 0x20, 0x4c, 0x0c, 0x00], // 0x25 '%'
+    [0x30, 0x48, 0x50, 0x20, 0x54, 0x48, 0x34, 0x00], // 0x26 '&'
+    [0x10, 0x10, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x27 "'"
+    [0x08, 0x10, 0x20, 0x20, 0x20, 0x10, 0x08, 0x00], // 0x28 '('
+    [0x20, 0x10, 0x08, 0x08, 0x08, 0x10, 0x20, 0x00], // 0x29 ')'
+    [0x00, 0x10, 0x54, 0x38, 0x54, 0x10, 0x00, 0x00], // 0x2a '*'
+    [0x00, 0x10, 0x10, 0x7c, 0x10, 0x10, 0x00, 0x00], // 0x2b '+'
+    [0x00, 0x00, 0x00, 0x00, 0x30, 0x10, 0x20, 0x00], // 0x2c ','
+    [0x00, 0x00, 0x00, 0x7c, 0x00, 0x00, 0x00, 0x00], // 0x2d '-'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x30, 0x30, 0x00], // 0x2e '.'
+    [0x00, 0x04, 0x08, 0x10, 0x20, 0x40, 0x00, 0x00], // 0x2f '/'
+    [0x38, 0x44, 0x4c, 0x54, 0x64, 0x44, 0x38, 0x00], // 0x30 '0'
+    [0x10, 0x30, 0x10, 0x10, 0x10, 0x10, 0x38, 0x00], // 0x31 '1'
+    [0x38, 0x44, 0x04, 0x08, 0x10, 0x20, 0x7c, 0x00], // 0x32 '2'
+    [0x7c, 0x08, 0x10, 0x08, 0x04, 0x44, 0x38, 0x00], // 0x33 '3'
+    [0x08, 0x18, 0x28, 0x48, 0x7c, 0x08, 0x08, 0x00], // 0x34 '4'
+    [0x7c, 0x40, 0x78, 0x04, 0x04, 0x44, 0x38, 0x00], // 0x35 '5'
+    [0x18, 0x20, 0x40, 0x78, 0x44, 0x44, 0x38, 0x00], // 0x36 '6'
+    [0x7c, 0x04, 0x08, 0x10, 0x20, 0x20, 0x20, 0x00], // 0x37 '7'
+    [0x38, 0x44, 0x44, 0x38, 0x44, 0x44, 0x38, 0x00], // 0x38 '8'
+    [0x38, 0x44, 0x44, 0x3c, 0x04, 0x08, 0x30, 0x00], // 0x39 '9'
+    [0x00, 0x30, 0x30, 0x00, 0x30, 0x30, 0x00, 0x00], // 0x3a ':'
+    [0x00, 0x30, 0x30, 0x00, 0x30, 0x10, 0x20, 0x00], // 0x3b ';'
+    [0x08, 0x10, 0x20, 0x40, 0x20, 0x10, 0x08, 0x00], // 0x3c '<'
+    [0x00, 0x00, 0x7c, 0x00, 0x7c, 0x00, 0x00, 0x00], // 0x3d '='
+    [0x20, 0x10, 0x08, 0x04, 0x08, 0x10, 0x20, 0x00], // 0x3e '>'
+    [0x38, 0x44, 0x04, 0x08, 0x10, 0x00, 0x10, 0x00], // 0x3f '?'
+    [0x38, 0x44, 0x04, 0x34, 0x54, 0x54, 0x38, 0x00], // 0x40 '@'
+    [0x38, 0x44, 0x44, 0x7c, 0x44, 0x44, 0x44, 0x00], // 0x41 'A'
+    [0x78, 0x44, 0x44, 0x78, 0x44, 0x44, 0x78, 0x00], // 0x42 'B'
+    [0x38, 0x44, 0x40, 0x40, 0x40, 0x44, 0x38, 0x00], // 0x43 'C'
+    [0x70, 0x48, 0x44, 0x44, 0x44, 0x48, 0x70, 0x00], // 0x44 'D'
+    [0x7c, 0x40, 0x40, 0x78, 0x40, 0x40, 0x7c, 0x00], // 0x45 'E'
+    [0x7c, 0x40, 0x40, 0x78, 0x40, 0x40, 0x40, 0x00], // 0x46 'F'
+    [0x38, 0x44, 0x40, 0x5c, 0x44, 0x44, 0x3c, 0x00], // 0x47 'G'
+    [0x44, 0x44, 0x44, 0x7c, 0x44, 0x44, 0x44, 0x00], // 0x48 'H'
+    [0x38, 0x10, 0x10, 0x10, 0x10, 0x10, 0x38, 0x00], // 0x49 'I'
+    [0x1c, 0x08, 0x08, 0x08, 0x08, 0x48, 0x30, 0x00], // 0x4a 'J'
+    [0x44, 0x48, 0x50, 0x60, 0x50, 0x48, 0x44, 0x00], // 0x4b 'K'
+    [0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x7c, 0x00], // 0x4c 'L'
+    [0x44, 0x6c, 0x54, 0x54, 0x44, 0x44, 0x44, 0x00], // 0x4d 'M'
+    [0x44, 0x44, 0x64, 0x54, 0x4c, 0x44, 0x44, 0x00], // 0x4e 'N'
+    [0x38, 0x44, 0x44, 0x44, 0x44, 0x44, 0x38, 0x00], // 0x4f 'O'
+    [0x78, 0x44, 0x44, 0x78, 0x40, 0x40, 0x40, 0x00], // 0x50 'P'
+    [0x38, 0x44, 0x44, 0x44, 0x54, 0x48, 0x34, 0x00], // 0x51 'Q'
+    [0x78, 0x44, 0x44, 0x78, 0x50, 0x48, 0x44, 0x00], // 0x52 'R'
+    [0x3c, 0x40, 0x40, 0x38, 0x04, 0x04, 0x78, 0x00], // 0x53 'S'
+    [0x7c, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x00], // 0x54 'T'
+    [0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x38, 0x00], // 0x55 'U'
+    [0x44, 0x44, 0x44, 0x44, 0x44, 0x28, 0x10, 0x00], // 0x56 'V'
+    [0x44, 0x44, 0x44, 0x54, 0x54, 0x54, 0x28, 0x00], // 0x57 'W'
+    [0x44, 0x44, 0x28, 0x10, 0x28, 0x44, 0x44, 0x00], // 0x58 'X'
+    [0x44, 0x44, 0x44, 0x28, 0x10, 0x10, 0x10, 0x00], // 0x59 'Y'
+    [0x7c, 0x04, 0x08, 0x10, 0x20, 0x40, 0x7c, 0x00], // 0x5a 'Z'
+    [0x38, 0x20, 0x20, 0x20, 0x20, 0x20, 0x38, 0x00], // 0x5b '['
+    [0x00, 0x40, 0x20, 0x10, 0x08, 0x04, 0x00, 0x00], // 0x5c '\\'
+    [0x38, 0x08, 0x08, 0x08, 0x08, 0x08, 0x38, 0x00], // 0x5d ']'
+    [0x10, 0x28, 0x44, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x5e '^'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7c], // 0x5f '_'
+    [0x20, 0x10, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00], // 0x60 '`'
+    [0x00, 0x00, 0x38, 0x04, 0x3c, 0x44, 0x3c, 0x00], // 0x61 'a'
+    [0x40, 0x40, 0x58, 0x64, 0x44, 0x44, 0x78, 0x00], // 0x62 'b'
+    [0x00, 0x00, 0x38, 0x40, 0x40, 0x44, 0x38, 0x00], // 0x63 'c'
+    [0x04, 0x04, 0x34, 0x4c, 0x44, 0x44, 0x3c, 0x00], // 0x64 'd'
+    [0x00, 0x00, 0x38, 0x44, 0x7c, 0x40, 0x38, 0x00], // 0x65 'e'
+    [0x18, 0x24, 0x20, 0x70, 0x20, 0x20, 0x20, 0x00], // 0x66 'f'
+    [0x00, 0x00, 0x3c, 0x44, 0x44, 0x3c, 0x04, 0x38], // 0x67 'g'
+    [0x40, 0x40, 0x58, 0x64, 0x44, 0x44, 0x44, 0x00], // 0x68 'h'
+    [0x10, 0x00, 0x30, 0x10, 0x10, 0x10, 0x38, 0x00], // 0x69 'i'
+    [0x08, 0x00, 0x18, 0x08, 0x08, 0x08, 0x48, 0x30], // 0x6a 'j'
+    [0x40, 0x40, 0x48, 0x50, 0x60, 0x50, 0x48, 0x00], // 0x6b 'k'
+    [0x30, 0x10, 0x10, 0x10, 0x10, 0x10, 0x38, 0x00], // 0x6c 'l'
+    [0x00, 0x00, 0x68, 0x54, 0x54, 0x44, 0x44, 0x00], // 0x6d 'm'
+    [0x00, 0x00, 0x58, 0x64, 0x44, 0x44, 0x44, 0x00], // 0x6e 'n'
+    [0x00, 0x00, 0x38, 0x44, 0x44, 0x44, 0x38, 0x00], // 0x6f 'o'
+    [0x00, 0x00, 0x78, 0x44, 0x44, 0x78, 0x40, 0x40], // 0x70 'p'
+    [0x00, 0x00, 0x3c, 0x44, 0x44, 0x3c, 0x04, 0x04], // 0x71 'q'
+    [0x00, 0x00, 0x58, 0x64, 0x40, 0x40, 0x40, 0x00], // 0x72 'r'
+    [0x00, 0x00, 0x3c, 0x40, 0x38, 0x04, 0x78, 0x00], // 0x73 's'
+    [0x20, 0x20, 0x70, 0x20, 0x20, 0x24, 0x18, 0x00], // 0x74 't'
+    [0x00, 0x00, 0x44, 0x44, 0x44, 0x4c, 0x34, 0x00], // 0x75 'u'
+    [0x00, 0x00, 0x44, 0x44, 0x44, 0x28, 0x10, 0x00], // 0x76 'v'
+    [0x00, 0x00, 0x44, 0x44, 0x54, 0x54, 0x28, 0x00], // 0x77 'w'
+    [0x00, 0x00, 0x44, 0x28, 0x10, 0x28, 0x44, 0x00], // 0x78 'x'
+    [0x00, 0x00, 0x44, 0x44, 0x44, 0x3c, 0x04, 0x38], // 0x79 'y'
+    [0x00, 0x00, 0x7c, 0x08, 0x10, 0x20, 0x7c, 0x00], // 0x7a 'z'
+    [0x08, 0x10, 0x10, 0x20, 0x10, 0x10, 0x08, 0x00], // 0x7b '{'
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x00], // 0x7c '|'
+    [0x20, 0x10, 0x10, 0x08, 0x10, 0x10, 0x20, 0x00], // 0x7d '}'
+    [0x00, 0x00, 0x20, 0x54, 0x08, 0x00, 0x00, 0x00], // 0x7e '~'
+    [0x7c, 0x44, 0x44, 0x44, 0x44, 0x44, 0x7c, 0x00], // 0x7f DEL (fallback)
+];
+
+/// Returns the glyph of the built-in font for `ch`, or the [`FALLBACK`] glyph if `ch` is not ASCII.
+#[must_use]
+pub fn glyph(ch: char) -> &'static [u8; 8] {
+    &DEFAULT[index(ch)]
+}
+
+/// A bitmap font of 8x8 glyphs (in the same layout as [`DEFAULT`]) with a horizontal advance for every glyph.
+/// Monospace fonts are the special case where all advances are equal.
+/// Characters outside of ASCII share the [`FALLBACK`] glyph and its advance.
+/// # Example
+/// ```rust
+/// use vason::font::Font;
+///
+/// let mono = Font::MONOSPACE;
+/// assert_eq!(mono.measure_text("Hi!"), (24, 8));
+///
+/// let mut font = Font::proportional();
+/// assert!(font.advance('i') < font.advance('m'));
+///
+/// font.set_advance(' ', 6);
+/// assert_eq!(font.advance(' '), 6);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Font {
+    glyphs: [[u8; 8]; 128],
+    advances: [i32; 128],
+}
+
+impl Font {
+    /// The built-in glyphs with every advance being [`GLYPH_WIDTH`]. This is what [`Canvas::draw_text`](crate::Canvas::draw_text) uses.
+    pub const MONOSPACE: Self = Self::monospace(DEFAULT);
+
+    /// Creates a monospace [`Font`] from a glyph table, every advance being [`GLYPH_WIDTH`].
+    #[must_use]
+    pub const fn monospace(glyphs: [[u8; 8]; 128]) -> Self {
+        Self {
+            glyphs,
+            advances: [GLYPH_WIDTH; 128],
+        }
+    }
+
+    /// Creates a proportional variant of the built-in font.
+    /// Glyphs are moved to the left edge and advance by their inked width plus one pixel of spacing.
+    /// Blank glyphs (such as space) advance by half of [`GLYPH_WIDTH`].
+    #[allow(clippy::cast_possible_wrap)]
+    #[must_use]
+    pub fn proportional() -> Self {
+        let mut font = Self::MONOSPACE;
+
+        for (glyph, advance) in font.glyphs.iter_mut().zip(font.advances.iter_mut()) {
+            let ink = glyph.iter().fold(0u8, |ink, row| ink | row);
+            if ink == 0 {
+                *advance = GLYPH_WIDTH / 2;
+                continue;
+            }
+
+            // keep one blank column on the left, just like the monospace glyphs
+            let shift = ink.leading_zeros().saturating_sub(1);
+            for row in glyph.iter_mut() {
+                *row <<= shift;
+            }
+
+            let inked_width = 8 - ink.leading_zeros() - ink.trailing_zeros();
+            *advance = inked_width as i32 + 1;
+        }
+
+        font
+    }
+
+    /// Sets the advance of the glyph of `ch` in pixels.
+    /// For characters outside of ASCII this sets the advance of the [`FALLBACK`] glyph.
+    pub fn set_advance(&mut self, ch: char, advance: i32) -> &mut Self {
+        self.advances[index(ch)] = advance;
+        self
+    }
+
+    /// Returns the advance of the glyph of `ch` in pixels.
+    #[must_use]
+    pub fn advance(&self, ch: char) -> i32 {
+        self.advances[index(ch)]
+    }
+
+    /// Returns the glyph of `ch`, or the [`FALLBACK`] glyph if `ch` is not ASCII.
+    #[must_use]
+    pub fn glyph(&self, ch: char) -> [u8; 8] {
+        self.glyphs[index(ch)]
+    }
+
+    /// Returns the width and height in pixels that `text` occupies when drawn with this [`Font`].
+    /// The width is the sum of the advances of the widest line, the height is [`GLYPH_HEIGHT`] per line
+    /// (`'\n'` starts a new line, so even empty text is one line high).
+    /// ```rust
+    /// use vason::font::Font;
+    ///
+    /// assert_eq!(Font::MONOSPACE.measure_text("ab\nabc"), (24, 16));
+    /// assert_eq!(Font::MONOSPACE.measure_text(""), (0, 8));
+    /// ```
+    #[must_use]
+    pub fn measure_text(&self, text: &str) -> (i32, i32) {
+        text.split('\n').fold((0, 0), |(width, height), line| {
+            let line_width = line
+                .chars()
+                .fold(0i32, |w, ch| w.saturating_add(self.advance(ch)));
+            (width.max(line_width), height.saturating_add(GLYPH_HEIGHT))
+        })
+    }
+
+    /// Breaks `text` into lines at word boundaries so that every line fits within `max_width` pixels,
+    /// and returns it with the lines separated by `'\n'`. Existing line breaks are kept, runs of whitespace between words collapse into a single space.
+    /// Words wider than `max_width` are broken between characters (a line always holds at least one character).
+    /// ```rust
+    /// use vason::font::Font;
+    ///
+    /// let font = Font::MONOSPACE;
+    /// assert_eq!(font.wrap_text("the quick brown fox", 80), "the quick\nbrown fox");
+    /// assert_eq!(font.wrap_text("abcdefgh", 24), "abc\ndef\ngh");
+    /// ```
+    #[must_use]
+    pub fn wrap_text(&self, text: &str, max_width: i32) -> String {
+        let text_width = |s: &str| {
+            s.chars()
+                .fold(0i32, |w, ch| w.saturating_add(self.advance(ch)))
+        };
+        let mut wrapped = String::with_capacity(text.len());
+
+        for (i, paragraph) in text.split('\n').enumerate() {
+            if i > 0 {
+                wrapped.push('\n');
+            }
+
+            let mut line_width = 0i32;
+            let mut line_empty = true;
+            for word in paragraph.split_whitespace() {
+                let word_width = text_width(word);
+                let space = self.advance(' ');
+
+                let extended = line_width.saturating_add(space).saturating_add(word_width);
+                if !line_empty && extended <= max_width {
+                    wrapped.push(' ');
+                    wrapped.push_str(word);
+                    line_width = extended;
+                    continue;
+                }
+
+                if !line_empty {
+                    wrapped.push('\n');
+                }
+
+                // hard break words that don't fit on a line of their own
+                line_width = 0;
+                line_empty = true;
+                for ch in word.chars() {
+                    let advance = self.advance(ch);
+                    if !line_empty && line_width.saturating_add(advance) > max_width {
+                        wrapped.push('\n');
+                        line_width = 0;
+                    }
+                    wrapped.push(ch);
+                    line_width = line_width.saturating_add(advance);
+                    line_empty = false;
+                }
+            }
+        }
+
+        wrapped
+    }
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Self::MONOSPACE
+    }
+}
+
+fn index(ch: char) -> usize {
+    let idx = ch as usize;
+    if idx < DEFAULT.len() {
+        idx
+    } else {
+        FALLBACK
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn printable_glyphs_are_visible() {
+        for ch in '!'..='~' {
+            assert!(glyph(ch).iter().any(|row| *row != 0), "{:?} is blank", ch);
+        }
+        assert!(glyph(' ').iter().all(|row| *row == 0));
+    }
+
+    #[test]
+    fn non_ascii_uses_fallback() {
+        assert_eq!(glyph('ő'), &DEFAULT[FALLBACK]);
+        assert_eq!(glyph('\u{80}'), &DEFAULT[FALLBACK]);
+    }
+
+    #[test]
+    fn proportional_glyphs_start_at_second_column() {
+        let font = Font::proportional();
+        for ch in '!'..='~' {
+            let ink = font.glyph(ch).iter().fold(0u8, |ink, row| ink | row);
+            assert_eq!(ink.leading_zeros(), 1, "{:?} is not left aligned", ch);
+            assert_eq!(font.advance(ch), 8 - ink.trailing_zeros() as i32);
+        }
+        assert_eq!(font.advance(' '), GLYPH_WIDTH / 2);
+        assert_eq!(
+            font.measure_text("il"),
+            (font.advance('i') + font.advance('l'), 8)
+        );
+    }
+
+    #[test]
+    fn wrap_text_fits_width() {
+        let font = Font::MONOSPACE;
+        assert_eq!(font.wrap_text("ab abcdefgh c", 32), "ab\nabcd\nefgh\nc");
+        assert_eq!(font.wrap_text("a  b\n\nc", 100), "a b\n\nc");
+        assert_eq!(font.wrap_text("ab", 0), "a\nb");
+
+        let font = Font::proportional();
+        let text = "Sphinx of black quartz, judge my vow! Pack my box with five dozen liquor jugs.";
+        for width in [20, 40, 64, 100] {
+            for line in font.wrap_text(text, width).lines() {
+                assert!(
+                    font.measure_text(line).0 <= width,
+                    "{:?} wider than {}",
+                    line,
+                    width
+                );
+            }
+        }
+
+        // huge advances don't overflow
+        let mut font = Font::MONOSPACE;
+        font.set_advance('b', i32::MAX);
+        assert_eq!(font.wrap_text("b aa", 10), "b\na\na");
+    }
+}
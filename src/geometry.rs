@@ -0,0 +1,172 @@
+//! Small geometry helpers.
+//! The drawing methods of [`Canvas`](crate::Canvas) take plain coordinates, so nothing here is required.
+//! Some of the most used methods accept any [`Coord`], so integers of other types and floats can be passed without casting.
+//! Methods that work on lists of points accept anything that implements `Into<Point>`,
+//! thus plain `(i32, i32)` tuples work just as well.
+
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A type that can be used as a coordinate (or size) in drawing methods.
+/// All drawing math is done with `i32`: integers that don't fit saturate at `i32::MIN`/`i32::MAX`,
+/// floats are rounded to the nearest integer (halfway cases away from zero) and saturate as well (NaN becomes 0).
+///
+/// # Examples
+///
+/// ```
+/// use vason::geometry::Coord;
+///
+/// assert_eq!(12usize.to_i32(), 12);
+/// assert_eq!(u64::MAX.to_i32(), i32::MAX);
+/// assert_eq!((-2.5f32).to_i32(), -3);
+/// assert_eq!(1e20f64.to_i32(), i32::MAX);
+/// ```
+pub trait Coord: Copy {
+    /// Converts this coordinate to an `i32`.
+    fn to_i32(self) -> i32;
+}
+
+macro_rules! impl_coord_int {
+    ($($t:ty),*) => {
+        $(
+            impl Coord for $t {
+                #[inline]
+                #[allow(unused_comparisons)]
+                fn to_i32(self) -> i32 {
+                    i32::try_from(self).unwrap_or(if self < 0 { i32::MIN } else { i32::MAX })
+                }
+            }
+        )*
+    };
+}
+
+impl_coord_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl Coord for f32 {
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    fn to_i32(self) -> i32 {
+        self.round() as i32
+    }
+}
+
+impl Coord for f64 {
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    fn to_i32(self) -> i32 {
+        self.round() as i32
+    }
+}
+
+/// A point (or vector) with integer coordinates.
+///
+/// # Examples
+///
+/// ```
+/// use vason::geometry::Point;
+///
+/// let a = Point::new(1, 2);
+/// let b = Point::from((3, 4));
+/// assert_eq!(a + b, Point::new(4, 6));
+/// assert_eq!(b - a, Point::new(2, 2));
+/// assert_eq!(a * 3, Point::new(3, 6));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    /// Creates a new [`Point`].
+    #[must_use]
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// The point at (0, 0).
+    pub const ORIGIN: Self = Self::new(0, 0);
+}
+
+impl From<(i32, i32)> for Point {
+    fn from((x, y): (i32, i32)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<[i32; 2]> for Point {
+    fn from([x, y]: [i32; 2]) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<Point> for (i32, i32) {
+    fn from(value: Point) -> Self {
+        (value.x, value.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Point::from((5, -3)), Point::new(5, -3));
+        assert_eq!(Point::from([5, -3]), Point::new(5, -3));
+        assert_eq!(<(i32, i32)>::from(Point::new(5, -3)), (5, -3));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mut p = Point::new(2, 3);
+        p += Point::new(1, 1);
+        assert_eq!(p, Point::new(3, 4));
+        p -= Point::new(3, 4);
+        assert_eq!(p, Point::ORIGIN);
+        assert_eq!(-Point::new(1, -2), Point::new(-1, 2));
+    }
+}
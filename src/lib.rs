@@ -1,9 +1,19 @@
 #![doc = include_str!("../README.md")]
 pub mod canvas;
 pub mod color;
+pub mod encoder;
+pub mod font;
+pub mod geometry;
+#[cfg(feature = "minifb")]
+pub mod live;
+pub mod owned;
 pub mod pen;
 pub mod ppm;
+pub mod supersample;
 
 pub use canvas::Canvas;
 pub use color::Color;
+pub use geometry::Point;
+pub use owned::OwnedCanvas;
 pub use pen::Pen;
+pub use supersample::SuperCanvas;
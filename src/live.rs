@@ -0,0 +1,47 @@
+//! A tiny helper for showing a [`Canvas`] in a window, available with the `minifb` feature.
+//! It takes care of the buffer, the window and the event loop so small demos can focus on drawing.
+//! # Example
+//! ```no_run
+//! use vason::Color;
+//!
+//! vason::live::run("Demo - ESC to exit", 640, 480, |canvas, frame| {
+//!     canvas.clear(Color::BLACK);
+//!     canvas.fill_circle((frame % 640) as i32, 240, 50, Color::RED);
+//! })
+//! .unwrap();
+//! ```
+
+use std::time::Duration;
+
+use minifb::{Key, Window, WindowOptions};
+
+use crate::Canvas;
+
+/// Opens a window of the given size and calls `draw` once per frame until the window is closed or ESC is pressed.
+/// `draw` receives a [`Canvas`] over the window's buffer (the previous frame's pixels are kept) and the index of the frame, starting at 0.
+/// The update rate is limited to ~60 fps.
+///
+/// # Errors
+///
+/// Returns an error if the window could not be created or updated.
+pub fn run(
+    title: &str,
+    width: usize,
+    height: usize,
+    mut draw: impl FnMut(&mut Canvas, u64),
+) -> Result<(), minifb::Error> {
+    let mut buffer = vec![0u32; width * height];
+    let mut window = Window::new(title, width, height, WindowOptions::default())?;
+
+    // Limit to max ~60 fps update rate
+    window.limit_update_rate(Some(Duration::from_micros(16600)));
+
+    let mut frame = 0;
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        draw(&mut Canvas::new(&mut buffer, width, height), frame);
+        window.update_with_buffer(&buffer, width, height)?;
+        frame += 1;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+//! A canvas that owns its pixels, for when bringing your own buffer is not needed.
+//! It also closes the load, edit, save loop for ppm files.
+//! # Example
+//! ```rust
+//! use vason::{Color, OwnedCanvas};
+//! let path = std::env::temp_dir().join("vason_owned_example.ppm");
+//!
+//! let mut image = OwnedCanvas::new(64, 64);
+//! image.canvas().fill_circle(32, 32, 20, Color::RED);
+//! image.save_to_path(&path).expect("could not save image");
+//!
+//! let mut image = OwnedCanvas::from_ppm_file(&path).expect("could not load image");
+//! image.canvas().fill_rect(0, 0, 8, 8, Color::BLUE);
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use crate::{ppm, Canvas};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+pub struct OwnedCanvas {
+    buffer: Vec<u32>,
+    width: usize,
+    height: usize,
+}
+
+impl OwnedCanvas {
+    /// Creates a new [`OwnedCanvas`] with given width and height. Every pixel starts out as 0 (transparent black).
+    /// # Panics
+    /// This function panics if `width * height` overflows a `usize`.
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        let size = width
+            .checked_mul(height)
+            .expect("width * height overflows usize");
+        Self {
+            buffer: vec![0; size],
+            width,
+            height,
+        }
+    }
+
+    /// Creates a new [`OwnedCanvas`] that takes ownership of `buffer`.
+    /// # Panics
+    /// This function panics if the supplied width and height does not match the buffer size
+    /// (`width * height` overflowing included).
+    #[must_use]
+    pub fn from_buffer(buffer: Vec<u32>, width: usize, height: usize) -> Self {
+        assert!(
+            width.checked_mul(height) == Some(buffer.len()),
+            "width * height does not match the buffer size"
+        );
+        Self {
+            buffer,
+            width,
+            height,
+        }
+    }
+
+    /// Reads a ppm image (see [`ppm::decode`]) into a new [`OwnedCanvas`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file could not be opened or read, or if it is not a valid ppm image.
+    /// The error message contains the path.
+    pub fn from_ppm_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let decoded = File::open(path).and_then(|file| ppm::decode(&mut BufReader::new(file)));
+
+        match decoded {
+            Ok((buffer, width, height)) => Ok(Self::from_buffer(buffer, width, height)),
+            #[allow(clippy::uninlined_format_args)]
+            Err(err) => Err(io::Error::new(
+                err.kind(),
+                format!("{}: {}", path.display(), err),
+            )),
+        }
+    }
+
+    /// Writes this [`OwnedCanvas`] to a ppm file (see [`ppm::encode_canvas`]), replacing the file if it exists.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file could not be created or there was an i/o error whilest writing.
+    pub fn save_to_path(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        ppm::encode_buffer(&self.buffer, self.width, self.height, &mut file)?;
+        file.flush()
+    }
+
+    /// Returns the width of this [`OwnedCanvas`].
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of this [`OwnedCanvas`].
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns a reference to the buffer of this [`OwnedCanvas`].
+    #[must_use]
+    pub fn buffer(&self) -> &[u32] {
+        &self.buffer
+    }
+
+    /// Returns a mutable reference to the buffer of this [`OwnedCanvas`].
+    #[must_use]
+    pub fn buffer_mut(&mut self) -> &mut [u32] {
+        &mut self.buffer
+    }
+
+    /// Consumes this [`OwnedCanvas`], returning its buffer.
+    #[must_use]
+    pub fn into_buffer(self) -> Vec<u32> {
+        self.buffer
+    }
+
+    /// Returns a [`Canvas`] to draw on this [`OwnedCanvas`] with.
+    /// ```rust
+    /// use vason::{Color, OwnedCanvas};
+    /// let mut image = OwnedCanvas::new(4, 4);
+    /// image.canvas().set_pixel(1, 2, Color::RED);
+    ///
+    /// assert_eq!(image.buffer()[2 * 4 + 1], Color::RED.0);
+    /// ```
+    #[must_use]
+    pub fn canvas(&mut self) -> Canvas<'_> {
+        Canvas::new(&mut self.buffer, self.width, self.height)
+    }
+}
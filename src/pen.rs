@@ -1,4 +1,7 @@
 //! The Pen-API allows you to play with ["turtle geometry"](https://people.eecs.berkeley.edu/~bh/v1ch10/turtle.html)
+//!
+//! Consecutive strokes make up a path whose pixels are drawn once, so with translucent colors or [`RasterOp::Xor`](crate::canvas::RasterOp::Xor)
+//! the joints look the same as the rest of the path. A path ends when the pen moves without drawing, or its color or thickness changes.
 //! # Example
 //! ```rust
 //! use vason::{Canvas, Color};
@@ -13,7 +16,9 @@
 //! });
 //! ```
 
-use crate::{Canvas, Color};
+use std::collections::HashSet;
+
+use crate::{geometry::Coord, Canvas, Color};
 
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Copy)]
@@ -42,6 +47,11 @@ impl Default for PenState {
 pub struct Pen<'a, 'b> {
     canvas: &'a mut Canvas<'b>,
     state: PenState,
+    distance_traveled: f32,
+    // the pixels drawn by the strokes of the current path, so the strokes don't draw them again where they meet
+    // (only tracked while redrawing pixels would show, e.g. with translucent colors)
+    path: HashSet<usize>,
+    path_style: (Color, i32),
 }
 
 impl<'a, 'b> Pen<'a, 'b> {
@@ -52,7 +62,13 @@ impl<'a, 'b> Pen<'a, 'b> {
 
     /// Creates a new [`Pen`] from the supplied state.
     pub fn with_state(canvas: &'a mut Canvas<'b>, state: PenState) -> Self {
-        let mut s = Self { canvas, state };
+        let mut s = Self {
+            canvas,
+            state,
+            distance_traveled: 0.0,
+            path: HashSet::new(),
+            path_style: (state.color, state.thickness),
+        };
         s.bound_self();
         s
     }
@@ -60,6 +76,7 @@ impl<'a, 'b> Pen<'a, 'b> {
     /// Sets the state of this [`Pen`].
     pub fn set_state(&mut self, state: PenState) -> &mut Self {
         self.state = state;
+        self.path.clear();
         self.bound_self();
         self
     }
@@ -104,12 +121,15 @@ impl<'a, 'b> Pen<'a, 'b> {
     /// Returns a mutable reference to the canvas of this [`Pen`].
     #[must_use]
     pub fn canvas_mut(&mut self) -> &mut Canvas<'b> {
+        self.path.clear();
         self.canvas
     }
 
-    /// Set the pen state to defaults [`Pen`].
+    /// Set the pen state to defaults [`Pen`] and zero its [`distance_traveled`](struct.Pen.html#method.distance_traveled).
     pub fn reset(&mut self) -> &mut Self {
         self.state = PenState::default();
+        self.distance_traveled = 0.0;
+        self.path.clear();
         self.bound_self();
         self
     }
@@ -118,6 +138,7 @@ impl<'a, 'b> Pen<'a, 'b> {
     /// In case you wish to draw a line when moving to new position use [`set_position_draw`](struct.Pen.html#method.set_position_draw)
     pub fn set_position(&mut self, x: f32, y: f32) -> &mut Self {
         self.state.position = self.bound_pos(x, y);
+        self.path.clear();
         self
     }
 
@@ -126,15 +147,13 @@ impl<'a, 'b> Pen<'a, 'b> {
     pub fn set_position_draw(&mut self, x: f32, y: f32) -> &mut Self {
         let (x, y) = self.bound_pos(x, y);
 
-        #[allow(clippy::cast_possible_truncation)]
         if self.state.is_down {
-            let x1 = self.state.position.0 as i32;
-            let y1 = self.state.position.1 as i32;
-
-            self.stroke(x1, y1, x as i32, y as i32);
+            self.stroke(self.state.position, (x, y));
+        } else {
+            self.path.clear();
         }
 
-        self.state.position = (x, y);
+        self.move_to((x, y));
 
         self
     }
@@ -145,6 +164,26 @@ impl<'a, 'b> Pen<'a, 'b> {
         self.state.position
     }
 
+    /// Returns the total length of the path this [`Pen`] moved along with [`forward`](struct.Pen.html#method.forward),
+    /// [`backward`](struct.Pen.html#method.backward) and [`set_position_draw`](struct.Pen.html#method.set_position_draw)
+    /// since it was created or last [`reset`](struct.Pen.html#method.reset), whether it was down or not.
+    /// ```rust
+    /// use vason::Canvas;
+    /// let mut buffer = vec![0u32; 64 * 64];
+    /// let mut canvas = Canvas::new(&mut buffer, 64, 64);
+    /// let mut pen = canvas.pen();
+    ///
+    /// pen.forward(10.0).backward(4.0).set_position_draw(6.0, 8.0);
+    /// assert_eq!(pen.distance_traveled(), 22.0);
+    ///
+    /// pen.reset();
+    /// assert_eq!(pen.distance_traveled(), 0.0);
+    /// ```
+    #[must_use]
+    pub fn distance_traveled(&self) -> f32 {
+        self.distance_traveled
+    }
+
     /// Move the pen forwards. Draws a line on it's way if the pen is down.
     pub fn forward(&mut self, amount: f32) -> &mut Self {
         let (dy, dx) = self.state.direction.sin_cos();
@@ -153,17 +192,13 @@ impl<'a, 'b> Pen<'a, 'b> {
             self.state.position.1 + dy * amount,
         );
 
-        #[allow(clippy::cast_possible_truncation)]
         if self.state.is_down {
-            let x1 = self.state.position.0 as i32;
-            let y1 = self.state.position.1 as i32;
-            let x2 = new_pos.0 as i32;
-            let y2 = new_pos.1 as i32;
-
-            self.stroke(x1, y1, x2, y2);
+            self.stroke(self.state.position, new_pos);
+        } else {
+            self.path.clear();
         }
 
-        self.state.position = new_pos;
+        self.move_to(new_pos);
 
         self
     }
@@ -175,13 +210,13 @@ impl<'a, 'b> Pen<'a, 'b> {
     }
 
     /// Initiate a flood fiil at current position.
-    #[allow(clippy::cast_possible_truncation)]
     pub fn flood_fill(&mut self) -> &mut Self {
         self.canvas.flood_fill(
-            self.state.position.0 as i32,
-            self.state.position.1 as i32,
+            self.state.position.0.to_i32(),
+            self.state.position.1.to_i32(),
             self.state.color,
         );
+        self.path.clear();
         self
     }
 
@@ -284,6 +319,75 @@ impl<'a, 'b> Pen<'a, 'b> {
         self
     }
 
+    /// Walks the pen around a closed regular polygon, turning right by the exterior angle after each side.
+    /// The pen ends up back at its start with its original direction (unless the polygon was clipped by the bounds).
+    /// Nothing happens if `sides` is less than 3.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = vec![0u32; 64 * 64];
+    /// let mut canvas = Canvas::new(&mut buffer, 64, 64);
+    /// let mut pen = canvas.pen();
+    ///
+    /// pen.set_position(20.0, 10.0).polygon(5, 25.0);
+    ///
+    /// let (x, y) = pen.get_position();
+    /// assert!((x - 20.0).abs() < 1e-3 && (y - 10.0).abs() < 1e-3);
+    /// assert_eq!(pen.get_direction(), 0.0);
+    /// ```
+    #[allow(clippy::cast_precision_loss)]
+    pub fn polygon(&mut self, sides: u32, side_length: f32) -> &mut Self {
+        if sides < 3 {
+            return self;
+        }
+
+        let direction = self.state.direction;
+        let exterior_angle = std::f32::consts::TAU / sides as f32;
+        for _ in 0..sides {
+            self.forward(side_length).turn_right_rad(exterior_angle);
+        }
+        self.state.direction = direction;
+
+        self
+    }
+
+    /// Draws a star with `points` tips as a single closed stroke of `points` segments of length `size`,
+    /// turning right by the star's point angle after each segment (144° for a 5-pointed star).
+    /// The pen ends up back at its start with its original direction, like with [`polygon`](struct.Pen.html#method.polygon).
+    /// The sharpest star that closes in one stroke is chosen, so 7-pointed stars turn by 3 × 360° / 7 each segment.
+    /// Nothing happens if `points` is less than 5 or exactly 6, as there is no such star.
+    /// ```rust
+    /// use vason::{Canvas, Color};
+    /// let mut buffer = vec![0u32; 64 * 64];
+    /// let mut canvas = Canvas::new(&mut buffer, 64, 64);
+    /// let mut pen = canvas.pen();
+    ///
+    /// pen.set_position(10.0, 25.0).set_color(Color::YELLOW).star(5, 45.0);
+    /// let (x, y) = pen.get_position();
+    /// assert!((x - 10.0).abs() < 1e-3 && (y - 25.0).abs() < 1e-3);
+    ///
+    /// pen.star(7, 40.0);
+    /// let (x, y) = pen.get_position();
+    /// assert!((x - 10.0).abs() < 1e-3 && (y - 25.0).abs() < 1e-3);
+    /// ```
+    #[allow(clippy::cast_precision_loss)]
+    pub fn star(&mut self, points: u32, size: f32) -> &mut Self {
+        // the largest step between tips that visits all of them before coming back
+        let step = (2..=points.saturating_sub(1) / 2)
+            .rev()
+            .find(|&step| gcd(points, step) == 1);
+
+        if let Some(step) = step {
+            let direction = self.state.direction;
+            let turn = std::f32::consts::TAU * step as f32 / points as f32;
+            for _ in 0..points {
+                self.forward(size).turn_right_rad(turn);
+            }
+            self.state.direction = direction;
+        }
+
+        self
+    }
+
     #[allow(clippy::similar_names)]
     fn bound_pos(&self, x: f32, y: f32) -> (f32, f32) {
         match self.state.bounds {
@@ -292,24 +396,44 @@ impl<'a, 'b> Pen<'a, 'b> {
         }
     }
 
+    fn move_to(&mut self, position: (f32, f32)) {
+        let (x, y) = self.state.position;
+        self.distance_traveled += (position.0 - x).hypot(position.1 - y);
+        self.state.position = position;
+    }
+
     fn bound_self(&mut self) {
         let (x, y) = self.state.position;
         self.state.position = self.bound_pos(x, y);
     }
 
-    fn stroke(&mut self, x1: i32, y1: i32, x2: i32, y2: i32) {
-        // thickness <= 1 checked by canvas.thick_line
-        self.canvas
-            .thick_line(x1, y1, x2, y2, self.state.thickness, self.state.color);
-
-        if self.state.thickness > 1 {
-            let half_thickness = self.state.thickness / 2;
+    // Positions are rounded to the nearest pixel, the same way the `*_f32` canvas methods do.
+    fn stroke(&mut self, from: (f32, f32), to: (f32, f32)) {
+        let (x1, y1) = (from.0.to_i32(), from.1.to_i32());
+        let (x2, y2) = (to.0.to_i32(), to.1.to_i32());
 
-            // TODO: optimize with kind of a dirty flag?
-            self.canvas
-                .fill_circle(x1, y1, half_thickness, self.state.color);
-            self.canvas
-                .fill_circle(x2, y2, half_thickness, self.state.color);
+        // a restyled stroke is drawn over the path like a new one
+        let style = (self.state.color, self.state.thickness);
+        if style != self.path_style {
+            self.path.clear();
+            self.path_style = style;
         }
+
+        self.canvas.pen_stroke(
+            (x1, y1),
+            (x2, y2),
+            self.state.thickness,
+            u32::from(self.state.color),
+            &mut self.path,
+        );
+    }
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        let rem = a % b;
+        a = b;
+        b = rem;
     }
+    a
 }
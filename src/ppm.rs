@@ -1,4 +1,4 @@
-//! The ppm module allows the user to save a canvas or a plain buffer to a file (or write it to anything that implements Write)
+//! The ppm module allows the user to save a canvas or a plain buffer to a file (or write it to anything that implements Write), and to read ppm images back.
 //! The ppm file format is one of the most simple ones. That is why it's included here. Not all image viewers support the format
 //! but the major ones usually do. (so do web browsers)
 //! # Example
@@ -15,8 +15,12 @@
 //! encode_canvas(&canvas, &mut file).expect("could not write image to file");
 //! ```
 
-use crate::Canvas;
-use std::io::{Result, Write};
+use crate::{color::PixelFormat, encoder::ImageEncoder, Canvas, Color};
+use std::{
+    fs::File,
+    io::{BufWriter, Error, ErrorKind, Read, Result, Write},
+    path::PathBuf,
+};
 
 /// Convenience function to encode a canvas to ppm format.
 /// ppm is supported by some main-stream image editors.
@@ -31,29 +35,521 @@ pub fn encode_canvas(canvas: &Canvas, w: &mut dyn Write) -> Result<()> {
 /// Encodes a buffer to ppm format.
 /// ppm is supported by some main-stream image editors.
 ///
+/// Every pixel is a `u32` of the form `0xAARRGGBB` (so its bytes are b, g, r, a in little-endian memory, see [`PixelFormat`]).
+/// Its r, g and b channels are written in that order, as the ppm format requires, and alpha is dropped.
+/// Use [`encode_buffer_with_order`] to swap red and blue.
+///
 /// # Errors
 ///
 /// This function will return an error if there was an i/o error whilest writing.
 pub fn encode_buffer(buffer: &[u32], width: usize, height: usize, w: &mut dyn Write) -> Result<()> {
-    #[allow(clippy::uninlined_format_args)]
-    writeln!(w, "P6 {} {} 255", width, height)?;
+    encode_buffer_with_order(buffer, width, height, w, ChannelOrder::Rgb)
+}
 
-    // instead of calling write on all pixels, we create chunks.
-    // this significantly increases performance even without the use of BufWriters.
-    // TODO: find a more reliable way of choosing a default chunk size (that also works well on other targets)
+/// Encodes a buffer to ppm format, writing the channels of every pixel in the given order.
+/// [`ChannelOrder::Rgb`] is what [`encode_buffer`] does; [`ChannelOrder::Bgr`] is for consumers
+/// that read the pixel data with red and blue swapped.
+///
+/// # Errors
+///
+/// This function will return an error if there was an i/o error whilest writing.
+/// ```rust
+/// use vason::{ppm::{self, ChannelOrder}, Color};
+/// let buffer = [Color::rgb(1, 2, 3).0];
+///
+/// let mut rgb = Vec::new();
+/// ppm::encode_buffer_with_order(&buffer, 1, 1, &mut rgb, ChannelOrder::Rgb).unwrap();
+/// assert!(rgb.ends_with(&[1, 2, 3]));
+///
+/// let mut bgr = Vec::new();
+/// ppm::encode_buffer_with_order(&buffer, 1, 1, &mut bgr, ChannelOrder::Bgr).unwrap();
+/// assert!(bgr.ends_with(&[3, 2, 1]));
+/// ```
+pub fn encode_buffer_with_order(
+    buffer: &[u32],
+    width: usize,
+    height: usize,
+    w: &mut dyn Write,
+    order: ChannelOrder,
+) -> Result<()> {
+    write_header(w, "P6", width, height, Some(255))?;
 
     // every pixel is represiented with three bytes so we skip the alpha channel.
-    // so our write chunk size is 2048 * 3 = 6144
+    match order {
+        ChannelOrder::Rgb => write_chunked(buffer, 3, w, |p, out| {
+            let (r, g, b, _) = PixelFormat::unpack(p);
+            out.copy_from_slice(&[r, g, b]);
+        }),
+        ChannelOrder::Bgr => write_chunked(buffer, 3, w, |p, out| {
+            let (r, g, b, _) = PixelFormat::unpack(p);
+            out.copy_from_slice(&[b, g, r]);
+        }),
+    }
+}
+
+/// The order in which the color channels of a pixel are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelOrder {
+    /// Red first, the order of the ppm format.
+    Rgb,
+    /// Blue first, like the bytes of a pixel in little-endian memory.
+    Bgr,
+}
+
+impl Default for ChannelOrder {
+    fn default() -> Self {
+        Self::Rgb
+    }
+}
+
+/// Convenience function to encode a canvas to 16-bit ppm format.
+/// See [`encode_buffer_16`].
+///
+/// # Errors
+///
+/// This function will return an error if there was an i/o error whilest writing.
+pub fn encode_canvas_16(canvas: &Canvas, w: &mut dyn Write) -> Result<()> {
+    encode_buffer_16(canvas.buffer(), canvas.width(), canvas.height(), w)
+}
+
+/// Encodes a buffer to 16-bit ppm format (maxval 65535), for pipelines that keep higher precision.
+/// Every 8-bit channel is bit-replicated to 16 bits (so 255 becomes 65535) and written big-endian.
+/// The output is twice the size of [`encode_buffer`]'s, which should be preferred otherwise.
+///
+/// # Errors
+///
+/// This function will return an error if there was an i/o error whilest writing.
+/// ```rust
+/// use vason::{ppm, Color};
+/// let buffer = [Color::rgb(255, 128, 0).0];
+///
+/// let mut bytes = Vec::new();
+/// ppm::encode_buffer_16(&buffer, 1, 1, &mut bytes).unwrap();
+///
+/// assert_eq!(bytes, b"P6 1 1 65535\n\xff\xff\x80\x80\x00\x00");
+/// assert_eq!(ppm::decode(&mut bytes.as_slice()).unwrap(), (buffer.to_vec(), 1, 1));
+/// ```
+pub fn encode_buffer_16(
+    buffer: &[u32],
+    width: usize,
+    height: usize,
+    w: &mut dyn Write,
+) -> Result<()> {
+    write_header(w, "P6", width, height, Some(65535))?;
+
+    write_chunked(buffer, 6, w, |p, out| {
+        let (r, g, b, _) = PixelFormat::unpack(p);
+        out.copy_from_slice(&[r, r, g, g, b, b]);
+    })
+}
+
+/// Convenience function to encode a canvas to grayscale pgm format.
+/// See [`encode_buffer_pgm`].
+///
+/// # Errors
+///
+/// This function will return an error if there was an i/o error whilest writing.
+pub fn encode_canvas_pgm(canvas: &Canvas, w: &mut dyn Write) -> Result<()> {
+    encode_buffer_pgm(canvas.buffer(), canvas.width(), canvas.height(), w)
+}
+
+/// Encodes a buffer to binary grayscale pgm format (P5), a third of the size of a ppm image.
+/// Useful for masks and heightmaps. Every pixel is stored as its [`luminance`](Color::luminance),
+/// that is `0.2126 * r + 0.7152 * g + 0.0722 * b` (Rec. 709 weights), alpha is ignored.
+///
+/// # Errors
+///
+/// This function will return an error if there was an i/o error whilest writing.
+/// ```rust
+/// use vason::{ppm, Color};
+/// let buffer = [Color::WHITE.0, Color::gray(77).0, Color::GREEN.0];
+///
+/// let mut bytes = Vec::new();
+/// ppm::encode_buffer_pgm(&buffer, 3, 1, &mut bytes).unwrap();
+///
+/// assert_eq!(bytes, b"P5 3 1 255\n\xff\x4d\xb6");
+/// ```
+pub fn encode_buffer_pgm(
+    buffer: &[u32],
+    width: usize,
+    height: usize,
+    w: &mut dyn Write,
+) -> Result<()> {
+    write_header(w, "P5", width, height, Some(255))?;
+
+    write_chunked(buffer, 1, w, |p, out| {
+        out[0] = Color(p).luminance();
+    })
+}
+
+/// Convenience function to encode a canvas to monochrome pbm format.
+/// See [`encode_buffer_pbm`].
+///
+/// # Errors
+///
+/// This function will return an error if there was an i/o error whilest writing.
+pub fn encode_canvas_pbm(canvas: &Canvas, w: &mut dyn Write, threshold: u8) -> Result<()> {
+    encode_buffer_pbm(
+        canvas.buffer(),
+        canvas.width(),
+        canvas.height(),
+        w,
+        threshold,
+    )
+}
+
+/// Encodes a buffer to binary monochrome pbm format (P4), for line art and e-ink-style targets.
+/// Pixels whose [`luminance`](Color::luminance) exceeds `threshold` are white, the rest are black.
+/// Every row is packed into bytes, 8 pixels per byte with the leftmost one in the most significant bit,
+/// and padded to a whole byte.
+///
+/// # Errors
+///
+/// This function will return an error if there was an i/o error whilest writing.
+/// ```rust
+/// use vason::{ppm, Color};
+/// let (b, w) = (Color::BLACK.0, Color::WHITE.0);
+/// let buffer = [
+///     b, w, w, w, w, w, w, w, b, b,
+///     w, w, w, w, w, w, w, w, w, w,
+/// ];
+///
+/// let mut bytes = Vec::new();
+/// ppm::encode_buffer_pbm(&buffer, 10, 2, &mut bytes, 128).unwrap();
+///
+/// // in pbm, a set bit means black
+/// assert_eq!(bytes, b"P4 10 2\n\x80\xc0\x00\x00");
+/// ```
+pub fn encode_buffer_pbm(
+    buffer: &[u32],
+    width: usize,
+    height: usize,
+    w: &mut dyn Write,
+    threshold: u8,
+) -> Result<()> {
+    write_header(w, "P4", width, height, None)?;
+    if width == 0 {
+        return Ok(());
+    }
+
+    let mut row_bytes = vec![0u8; (width + 7) / 8];
+    for row in buffer.chunks_exact(width) {
+        row_bytes.fill(0);
+        for (x, p) in row.iter().enumerate() {
+            if Color(*p).luminance() <= threshold {
+                row_bytes[x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        w.write_all(&row_bytes)?;
+    }
+
+    Ok(())
+}
+
+/// Decodes a ppm image with any maxval, returning its pixels along with its width and height.
+/// Both binary (P6) and ascii (P3) images are supported, comments included.
+/// Channels are rescaled to 8 bits and every pixel is opaque.
+///
+/// # Errors
+///
+/// This function will return an error if there was an i/o error whilest reading,
+/// or an [`InvalidData`](ErrorKind::InvalidData) error if the data is not a supported ppm image
+/// (other netpbm formats like P1 or P2 included).
+/// ```rust
+/// use vason::{ppm, Color};
+/// let buffer = [Color::RED.0, Color::rgb(1, 2, 3).0];
+///
+/// let mut bytes = Vec::new();
+/// ppm::encode_buffer(&buffer, 2, 1, &mut bytes).unwrap();
+/// assert_eq!(ppm::decode(&mut bytes.as_slice()).unwrap(), (buffer.to_vec(), 2, 1));
+///
+/// let ascii = b"P3\n# a comment\n2 1\n255\n255 0 0   1 2\n3\n";
+/// assert_eq!(ppm::decode(&mut &ascii[..]).unwrap(), (buffer.to_vec(), 2, 1));
+///
+/// assert!(ppm::decode(&mut &b"P6 2 1 255\n"[..]).is_err());
+/// assert!(ppm::decode(&mut &b"P2 1 1 255\n0\n"[..]).is_err());
+/// ```
+pub fn decode(r: &mut dyn Read) -> Result<(Vec<u32>, usize, usize)> {
+    let mut bytes = Vec::new();
+    r.read_to_end(&mut bytes)?;
+    let mut tokens = Tokens {
+        bytes: &bytes,
+        pos: 0,
+    };
+
+    let ascii = match tokens.token()? {
+        b"P3" => true,
+        b"P6" => false,
+        magic => {
+            #[allow(clippy::uninlined_format_args)]
+            let message = format!(
+                "unsupported magic number {:?}",
+                String::from_utf8_lossy(magic)
+            );
+            return Err(invalid_data(&message));
+        }
+    };
+
+    let width = tokens.number()?;
+    let height = tokens.number()?;
+    let maxval = tokens.number()?;
+    if maxval == 0 || maxval > 65535 {
+        return Err(invalid_data("maxval out of range"));
+    }
+    let len = width
+        .checked_mul(height)
+        .ok_or_else(|| invalid_data("image too large"))?;
+
+    let pixels = if ascii {
+        (0..len)
+            .map(|_| {
+                let (r, g, b) = (tokens.number()?, tokens.number()?, tokens.number()?);
+                Ok(Color::rgb(rescale(r, maxval), rescale(g, maxval), rescale(b, maxval)).0)
+            })
+            .collect::<Result<_>>()?
+    } else {
+        // exactly one whitespace character separates the header from the raster
+        let raster = &bytes[(tokens.pos + 1).min(bytes.len())..];
+        decode_raster(raster, len, maxval)?
+    };
+
+    Ok((pixels, width, height))
+}
+
+fn decode_raster(raster: &[u8], len: usize, maxval: usize) -> Result<Vec<u32>> {
+    let sample_len = if maxval < 256 { 1 } else { 2 };
+    if raster.len() / (3 * sample_len) < len {
+        return Err(invalid_data("not enough pixel data"));
+    }
+
+    Ok(raster
+        .chunks_exact(3 * sample_len)
+        .take(len)
+        .map(|pixel| {
+            let channel = |i: usize| {
+                let sample = &pixel[i * sample_len..(i + 1) * sample_len];
+                rescale(
+                    sample.iter().fold(0, |v, &b| v << 8 | usize::from(b)),
+                    maxval,
+                )
+            };
+            Color::rgb(channel(0), channel(1), channel(2)).0
+        })
+        .collect())
+}
+
+/// Writes successive canvases to a directory as numbered ppm files (`frame_0001.ppm`, `frame_0002.ppm`, ...),
+/// so an animation can be rendered offline and assembled into a video with external tools, e.g.
+/// `ffmpeg -i frame_%04d.ppm out.mp4`.
+/// ```rust
+/// use vason::{ppm::Animation, Canvas, Color};
+/// let dir = std::env::temp_dir().join("vason_animation_example");
+/// std::fs::create_dir_all(&dir).unwrap();
+///
+/// let mut buffer = vec![0u32; 32 * 32];
+/// let mut animation = Animation::new(&dir, "frame");
+/// for x in 0..3 {
+///     let mut canvas = Canvas::new(&mut buffer, 32, 32);
+///     canvas.clear(Color::BLACK);
+///     canvas.fill_circle(x * 8, 16, 4, Color::RED);
+///     animation.push_frame(&canvas).unwrap();
+/// }
+///
+/// assert_eq!(animation.frame_count(), 3);
+/// assert!(dir.join("frame_0003.ppm").exists());
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub struct Animation {
+    dir: PathBuf,
+    base_name: String,
+    frame_count: usize,
+}
+
+impl Animation {
+    /// Creates a new [`Animation`] that writes its frames to `dir`, which must already exist.
+    /// Frames are named `<base_name>_<index>.ppm`, the index starts at 1 and is zero-padded to 4 digits.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>, base_name: &str) -> Self {
+        Self {
+            dir: dir.into(),
+            base_name: base_name.to_owned(),
+            frame_count: 0,
+        }
+    }
+
+    /// Returns the number of frames written so far.
+    #[must_use]
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// Returns the path the next frame will be written to.
+    #[must_use]
+    pub fn next_path(&self) -> PathBuf {
+        #[allow(clippy::uninlined_format_args)]
+        let file_name = format!("{}_{:04}.ppm", self.base_name, self.frame_count + 1);
+        self.dir.join(file_name)
+    }
+
+    /// Writes `canvas` as the next frame.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file could not be created or there was an i/o error whilest writing.
+    /// The frame is not counted in that case, so pushing again retries the same file.
+    pub fn push_frame(&mut self, canvas: &Canvas) -> Result<()> {
+        let mut file = BufWriter::new(File::create(self.next_path())?);
+        encode_canvas(canvas, &mut file)?;
+        file.flush()?;
+
+        self.frame_count += 1;
+        Ok(())
+    }
+}
+
+/// The [`ImageEncoder`] for 8-bit ppm, see [`encode_canvas`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ppm;
+
+impl ImageEncoder for Ppm {
+    fn encode(&self, canvas: &Canvas, w: &mut dyn Write) -> Result<()> {
+        encode_canvas(canvas, w)
+    }
+
+    fn extension(&self) -> &'static str {
+        "ppm"
+    }
+}
+
+/// The [`ImageEncoder`] for 16-bit ppm, see [`encode_canvas_16`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ppm16;
+
+impl ImageEncoder for Ppm16 {
+    fn encode(&self, canvas: &Canvas, w: &mut dyn Write) -> Result<()> {
+        encode_canvas_16(canvas, w)
+    }
+
+    fn extension(&self) -> &'static str {
+        "ppm"
+    }
+}
+
+/// The [`ImageEncoder`] for grayscale pgm, see [`encode_canvas_pgm`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pgm;
+
+impl ImageEncoder for Pgm {
+    fn encode(&self, canvas: &Canvas, w: &mut dyn Write) -> Result<()> {
+        encode_canvas_pgm(canvas, w)
+    }
+
+    fn extension(&self) -> &'static str {
+        "pgm"
+    }
+}
+
+/// The [`ImageEncoder`] for monochrome pbm, see [`encode_canvas_pbm`].
+#[derive(Debug, Clone, Copy)]
+pub struct Pbm {
+    /// Pixels whose luminance exceeds the threshold are white, the rest are black.
+    pub threshold: u8,
+}
+
+impl ImageEncoder for Pbm {
+    fn encode(&self, canvas: &Canvas, w: &mut dyn Write) -> Result<()> {
+        encode_canvas_pbm(canvas, w, self.threshold)
+    }
+
+    fn extension(&self) -> &'static str {
+        "pbm"
+    }
+}
+
+// Writes a netpbm header, maxval is omitted for bitmaps.
+fn write_header(
+    w: &mut dyn Write,
+    magic: &str,
+    width: usize,
+    height: usize,
+    maxval: Option<usize>,
+) -> Result<()> {
+    #[allow(clippy::uninlined_format_args)]
+    match maxval {
+        Some(maxval) => writeln!(w, "{} {} {} {}", magic, width, height, maxval),
+        None => writeln!(w, "{} {} {}", magic, width, height),
+    }
+}
+
+// Writes every pixel as `pixel_len` bytes produced by `encode`.
+// instead of calling write on all pixels, we create chunks.
+// this significantly increases performance even without the use of BufWriters.
+// TODO: find a more reliable way of choosing a default chunk size (that also works well on other targets)
+fn write_chunked(
+    buffer: &[u32],
+    pixel_len: usize,
+    w: &mut dyn Write,
+    encode: impl Fn(u32, &mut [u8]),
+) -> Result<()> {
     // TODO: is this too janky? Should just the user use BufWriters?
-    let mut tmp_buffer = vec![0u8; 6144];
+    let mut tmp_buffer = vec![0u8; 2048 * pixel_len];
     for chunk in buffer.chunks(2048) {
-        chunk
-            .iter()
-            .flat_map(|p| p.to_be_bytes().into_iter().skip(1))
-            .enumerate()
-            .for_each(|(i, b)| tmp_buffer[i] = b);
-        w.write_all(&tmp_buffer)?;
+        let bytes = &mut tmp_buffer[..chunk.len() * pixel_len];
+        for (p, out) in chunk.iter().zip(bytes.chunks_exact_mut(pixel_len)) {
+            encode(*p, out);
+        }
+        w.write_all(bytes)?;
     }
 
     Ok(())
 }
+
+#[allow(clippy::cast_possible_truncation)]
+fn rescale(value: usize, maxval: usize) -> u8 {
+    if maxval == 255 {
+        value.min(255) as u8
+    } else {
+        ((value.min(maxval) * 255 + maxval / 2) / maxval) as u8
+    }
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+// The whitespace separated fields of a pnm header (and of an ascii raster), which may contain comments.
+struct Tokens<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn token(&mut self) -> Result<&'a [u8]> {
+        loop {
+            match self.bytes.get(self.pos) {
+                Some(b'#') => {
+                    while !matches!(self.bytes.get(self.pos), Some(b'\n' | b'\r') | None) {
+                        self.pos += 1;
+                    }
+                }
+                Some(b) if b.is_ascii_whitespace() => self.pos += 1,
+                Some(_) => break,
+                None => return Err(invalid_data("unexpected end of data")),
+            }
+        }
+
+        let start = self.pos;
+        while matches!(self.bytes.get(self.pos), Some(b) if !b.is_ascii_whitespace() && *b != b'#')
+        {
+            self.pos += 1;
+        }
+        Ok(&self.bytes[start..self.pos])
+    }
+
+    fn number(&mut self) -> Result<usize> {
+        std::str::from_utf8(self.token()?)
+            .ok()
+            .and_then(|token| token.parse().ok())
+            .ok_or_else(|| invalid_data("invalid number"))
+    }
+}
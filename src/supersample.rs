@@ -0,0 +1,112 @@
+//! Cheap anti-aliasing for every primitive by supersampling (SSAA).
+//! Draw on a [`Canvas`] that is `factor` times larger in both directions, then [`SuperCanvas::resolve`]
+//! averages each `factor`×`factor` block down into the target buffer.
+//!
+//! Memory cost: the supersampled buffer holds `width * height * factor²` pixels
+//! (a 1920×1080 target at factor 4 needs ~127 MiB on top of the target buffer), so 2 or 4 are the sensible factors.
+//! Remember to scale coordinates, sizes and thicknesses by the factor as well.
+//! # Example
+//! ```rust
+//! use vason::{Color, SuperCanvas};
+//!
+//! let mut buffer = vec![0u32; 64 * 64];
+//! let mut ssaa = SuperCanvas::new(&mut buffer, 64, 64, 4);
+//! ssaa.canvas().fill_circle(32 * 4, 32 * 4, 20 * 4, Color::WHITE);
+//! ssaa.resolve();
+//! ```
+
+use crate::Canvas;
+
+pub struct SuperCanvas<'a> {
+    target: &'a mut [u32],
+    width: usize,
+    height: usize,
+    factor: usize,
+    buffer: Vec<u32>,
+}
+
+impl<'a> SuperCanvas<'a> {
+    /// Creates a new [`SuperCanvas`] that resolves into `target` (of size `width` × `height`).
+    /// The supersampled buffer is allocated here and starts out black.
+    /// # Panics
+    /// This function panics if the supplied width and height does not match the buffer size, if `factor` is 0,
+    /// or if the size of the supersampled buffer overflows a `usize`.
+    #[must_use]
+    pub fn new(target: &'a mut [u32], width: usize, height: usize, factor: usize) -> Self {
+        assert!(
+            width.checked_mul(height) == Some(target.len()),
+            "width * height does not match the buffer size"
+        );
+        assert!(factor > 0, "the supersampling factor must be positive");
+        let size = width
+            .checked_mul(factor)
+            .zip(height.checked_mul(factor))
+            .and_then(|(width, height)| width.checked_mul(height))
+            .expect("the supersampled canvas is too large");
+        Self {
+            target,
+            width,
+            height,
+            factor,
+            buffer: vec![0; size],
+        }
+    }
+
+    /// Returns the supersampling factor of this [`SuperCanvas`].
+    #[must_use]
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Returns a [`Canvas`] over the supersampled buffer (`width * factor` × `height * factor`).
+    /// What's drawn there persists between calls until it's cleared.
+    #[must_use]
+    pub fn canvas(&mut self) -> Canvas<'_> {
+        Canvas::new(
+            &mut self.buffer,
+            self.width * self.factor,
+            self.height * self.factor,
+        )
+    }
+
+    /// Averages the supersampled buffer down into the target buffer (every channel, alpha included).
+    /// # Example
+    /// ```rust
+    /// use vason::{Color, SuperCanvas};
+    ///
+    /// let mut buffer = vec![0u32; 1];
+    /// let mut ssaa = SuperCanvas::new(&mut buffer, 1, 1, 2);
+    /// ssaa.canvas().fill_rect(0, 0, 1, 2, Color::WHITE);
+    /// ssaa.resolve();
+    /// // half of the samples are white, half are transparent black
+    /// assert_eq!(buffer[0], 0x8080_8080);
+    /// ```
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn resolve(&mut self) {
+        let factor = self.factor;
+        let super_width = self.width * factor;
+        let samples = (factor * factor) as u64;
+
+        if self.width == 0 {
+            return;
+        }
+
+        for (y, row) in self.target.chunks_exact_mut(self.width).enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                let mut sums = [0u64; 4];
+                for sy in y * factor..(y + 1) * factor {
+                    let start = sy * super_width + x * factor;
+                    for sample in &self.buffer[start..start + factor] {
+                        for (sum, byte) in sums.iter_mut().zip(sample.to_le_bytes()) {
+                            *sum += u64::from(byte);
+                        }
+                    }
+                }
+
+                // rounded average, always fits in a u8
+                let avg = sums.map(|sum| ((sum + samples / 2) / samples) as u8);
+                *pixel = u32::from_le_bytes(avg);
+            }
+        }
+    }
+}